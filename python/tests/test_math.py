@@ -1,4 +1,5 @@
 import math
+from fractions import Fraction
 
 import pytest
 import rstd.math as rust_math
@@ -56,3 +57,78 @@ def test_isqrt_float_value_produces_type_error():
         rust_math.isqrt(8.9)
 
     assert type(python_error.value) == type(rust_error.value)
+
+
+def test_comb_matches():
+    for n in range(200):
+        for k in range(n + 2):
+            expected = math.comb(n, k)
+            actual = rust_math.comb(n, k)
+            assert actual == expected
+
+
+def test_comb_negative_k_raises_value_error():
+    with pytest.raises(ValueError) as python_error:
+        math.comb(5, -1)
+
+    with pytest.raises(ValueError) as rust_error:
+        rust_math.comb(5, -1)
+
+    assert type(python_error.value) == type(rust_error.value)
+    assert str(rust_error.value) == "k must be a non-negative integer"
+
+
+def test_gcd_iter_accepts_any_iterable():
+    assert rust_math.gcd_iter(range(4, 40, 4)) == 4
+    assert rust_math.gcd_iter(n * 6 for n in range(1, 10)) == 6
+    assert rust_math.gcd_iter([2**100, 2**70 * 3, -(2**80)]) == 2**70
+    assert rust_math.gcd_iter([]) == math.gcd()
+
+
+def test_gcd_iter_float_value_produces_type_error():
+    with pytest.raises(TypeError) as python_error:
+        math.gcd(4, 8.0)
+
+    with pytest.raises(TypeError) as rust_error:
+        rust_math.gcd_iter([4, 8.0])
+
+    assert type(python_error.value) == type(rust_error.value)
+
+
+def test_write_primes_round_trips(tmp_path):
+    path = tmp_path / "primes.txt"
+    rust_math.write_primes(10, 50, path, sep=",")
+    assert path.read_text() == "11,13,17,19,23,29,31,37,41,43,47"
+
+
+def test_rational_approximation_matches_limit_denominator():
+    for x in [math.pi, -math.pi, math.e, 0.1, 0.5, -2.75, 1e-9, 12345.678]:
+        for max_denominator in [1, 2, 7, 10, 113, 1000, 10**6]:
+            expected = Fraction(x).limit_denominator(max_denominator)
+            actual = rust_math.rational_approximation(x, max_denominator)
+            assert actual == (expected.numerator, expected.denominator)
+
+
+def test_is_prime_handles_ints_beyond_64_bits():
+    assert rust_math.is_prime(2**100) is False
+    assert rust_math.is_prime(2**127 - 1) is True
+    assert rust_math.is_prime(2**61 - 1) is True
+
+    with pytest.raises(OverflowError):
+        rust_math.is_prime_trace(2**100)
+
+    with pytest.raises(OverflowError):
+        rust_math.is_prime(-7)
+
+
+def test_factorial_as_round_trips():
+    expected = math.factorial(200)
+
+    assert rust_math.factorial_as(200, "int") == expected
+    assert int(rust_math.factorial_as(200, "hex"), 16) == expected
+    as_bytes = rust_math.factorial_as(200, "bytes")
+    assert isinstance(as_bytes, bytes)
+    assert int.from_bytes(as_bytes, "big") == expected
+
+    with pytest.raises(ValueError):
+        rust_math.factorial_as(200, "octal")
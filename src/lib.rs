@@ -1,13 +1,146 @@
-#![feature(const_for, test)]
-mod math;
+#![feature(float_gamma)]
+#![cfg_attr(test, feature(test))]
+pub mod linear_sieve;
+pub mod math;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
+#[cfg(feature = "python")]
+#[allow(clippy::too_many_lines)]
 fn register_math_submodule(parent_module: &Bound<'_, PyModule>) -> PyResult<()> {
     let submodule = PyModule::new(parent_module.py(), "math")?;
     submodule.add_function(wrap_pyfunction!(math::factorial, &submodule)?)?;
     submodule.add_function(wrap_pyfunction!(math::comb, &submodule)?)?;
     submodule.add_function(wrap_pyfunction!(math::isqrt, &submodule)?)?;
     submodule.add_function(wrap_pyfunction!(math::perm, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::primorial_power, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::random_prime, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::binomial_pmf, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::binomial_probability_exact,
+        &submodule
+    )?)?;
+    submodule.add_function(wrap_pyfunction!(math::enumerate_primes, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::primorial_mod, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::comb_estimate, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::twin_prime_count, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::ipow_checked, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::first_n_primes, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::prime_with_bit_length, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_prime_trace, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::factorial_factorization, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::count_primes_by_last_digit,
+        &submodule
+    )?)?;
+    submodule.add_function(wrap_pyfunction!(math::primorial_iterative, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_carmichael, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::sum_reciprocal_primes_exact,
+        &submodule
+    )?)?;
+    submodule.add_function(wrap_pyfunction!(math::zeta_euler_product, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::liouville, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::liouville_sum, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::clear_math_cache, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::factorial_float, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::gcd_iter, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::next_gap_at_least, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::inverse_factorial, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::write_primes, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::von_mangoldt, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::chebyshev_psi, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::chebyshev_theta, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::comb_p_adic_valuation, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::product_tree_py, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::divisor_summatory, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::farey_sequence, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::rational_approximation, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::pascal_row_mod, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::count_primes_congruent, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::factorial_digit_chunks_py,
+        &submodule
+    )?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::multiplicative_persistence,
+        &submodule
+    )?)?;
+    submodule.add_function(wrap_pyfunction!(math::reset_prime_cache, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::smarandache, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::abundancy_index, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::amicable_pair, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_prime, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::count_primes_in_bertrand_interval,
+        &submodule
+    )?)?;
+    submodule.add_function(wrap_pyfunction!(math::prime_signature, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::jordan_totient, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::comb_batch, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::totient_summatory, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::mobius, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::mertens, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::totient_image_count, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::repunit_period, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::convergents_of_e, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_primorial_prime, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_factorial_prime, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::rational_pow, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::ackermann, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_palindrome, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::reverse_digits, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::collatz_length, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::goldbach_count, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::count_prime_triplets, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_factorion, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::random_smooth_number, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::largest_prime_factor_big,
+        &submodule
+    )?)?;
+    submodule.add_function(wrap_pyfunction!(math::factorize_u64, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::aliquot_sum, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::aliquot_sequence_py, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_practical, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::factorial_as, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::count_primes_with_binary_weight,
+        &submodule
+    )?)?;
+    submodule.add_function(wrap_pyfunction!(math::nth_prime_fast, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::polynomial_prime_values, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::radical, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::radical_range, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::prime_compositions, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::prime_partitions, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::sophie_germain_primes, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_safe_prime, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::prime_quadruplets, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::gcd_sum, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::sum_of_two_squares_count,
+        &submodule
+    )?)?;
+    submodule.add_function(wrap_pyfunction!(math::totient, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::totient_chain_length, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::comb_factorization, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::factorial_log10, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::primes_matching, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::product_range_mod, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::comb_trailing_zeros, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::figurate, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_figurate, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::comb_is_odd, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::sum_prime_digit_sums, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::fraction_period, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::dedekind_psi, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::is_harshad, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(math::next_harshad, &submodule)?)?;
+    submodule.add_function(wrap_pyfunction!(
+        math::ways_as_sum_of_two_cubes,
+        &submodule
+    )?)?;
     parent_module
         .py()
         .import("sys")?
@@ -16,6 +149,7 @@ fn register_math_submodule(parent_module: &Bound<'_, PyModule>) -> PyResult<()>
     parent_module.add_submodule(&submodule)
 }
 
+#[cfg(feature = "python")]
 #[pymodule]
 fn rstd(m: &Bound<'_, PyModule>) -> PyResult<()> {
     register_math_submodule(m)?;
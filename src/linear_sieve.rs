@@ -0,0 +1,146 @@
+/// Smallest prime factors, primes, Möbius values and totients for every `k <= n`, built in one pass
+///
+/// Euler's linear sieve crosses each composite off exactly once, through its smallest prime factor `p`.
+/// Writing `k = p * m`, the multiplicative values follow from those of `m`: when `p | m` the Möbius value
+/// is 0 and the totient is `phi(m) * p`, otherwise they are `-mu(m)` and `phi(m) * (p - 1)`
+pub struct LinearSieve {
+    smallest_prime_factors: Vec<u64>,
+    primes: Vec<u64>,
+    mobius: Vec<i8>,
+    totients: Vec<u64>,
+}
+
+impl LinearSieve {
+    /// Sieve every `k <= n` in a single linear pass
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` does not fit in a `usize`.
+    #[must_use]
+    pub fn new(n: u64) -> Self {
+        let limit = usize::try_from(n).expect("sieve limit must fit in usize");
+        let mut smallest_prime_factors = vec![0_u64; limit + 1];
+        let mut primes = Vec::new();
+        let mut mobius = vec![0_i8; limit + 1];
+        let mut totients = vec![0_u64; limit + 1];
+        if limit >= 1 {
+            smallest_prime_factors[1] = 1;
+            mobius[1] = 1;
+            totients[1] = 1;
+        }
+        for m in 2..=limit {
+            let value = m as u64;
+            if smallest_prime_factors[m] == 0 {
+                smallest_prime_factors[m] = value;
+                primes.push(value);
+                mobius[m] = -1;
+                totients[m] = value - 1;
+            }
+            for &p in &primes {
+                if p > smallest_prime_factors[m] {
+                    break;
+                }
+                let Some(multiple) = usize::try_from(p)
+                    .ok()
+                    .and_then(|p| m.checked_mul(p))
+                    .filter(|&multiple| multiple <= limit)
+                else {
+                    break;
+                };
+                smallest_prime_factors[multiple] = p;
+                if p == smallest_prime_factors[m] {
+                    mobius[multiple] = 0;
+                    totients[multiple] = totients[m] * p;
+                } else {
+                    mobius[multiple] = -mobius[m];
+                    totients[multiple] = totients[m] * (p - 1);
+                }
+            }
+        }
+        Self {
+            smallest_prime_factors,
+            primes,
+            mobius,
+            totients,
+        }
+    }
+
+    /// The largest `k` the sieve covers
+    #[must_use]
+    pub fn limit(&self) -> u64 {
+        self.totients.len() as u64 - 1
+    }
+
+    /// Every prime up to the limit in ascending order
+    #[must_use]
+    pub fn primes(&self) -> &[u64] {
+        &self.primes
+    }
+
+    /// The smallest prime factor of each `k`, indexed by `k`, with 0 for `k = 0` and 1 for `k = 1`
+    #[must_use]
+    pub fn smallest_prime_factors(&self) -> &[u64] {
+        &self.smallest_prime_factors
+    }
+
+    /// The Möbius function of each `k`, indexed by `k`, with 0 for `k = 0`
+    #[must_use]
+    pub fn mobius(&self) -> &[i8] {
+        &self.mobius
+    }
+
+    /// Euler's totient of each `k`, indexed by `k`, with 0 for `k = 0`
+    #[must_use]
+    pub fn totients(&self) -> &[u64] {
+        &self.totients
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{jordan_totient, mobius, primes_up_to};
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_linear_sieve_matches_factorization() {
+        let n = 100_000;
+        let sieve = LinearSieve::new(n);
+        assert_eq!(sieve.limit(), n);
+        assert_eq!(sieve.primes(), primes_up_to(n));
+        for k in 1..=n {
+            let index = usize::try_from(k).unwrap();
+            assert_eq!(sieve.mobius()[index], mobius(k).unwrap(), "mu({k})");
+            assert_eq!(
+                BigUint::from(sieve.totients()[index]),
+                jordan_totient(k, 1).unwrap(),
+                "phi({k})"
+            );
+            let spf = sieve.smallest_prime_factors()[index];
+            if k > 1 {
+                assert!(k.is_multiple_of(spf) && sieve.primes().binary_search(&spf).is_ok());
+                assert!(
+                    sieve
+                        .primes()
+                        .iter()
+                        .take_while(|&&p| p < spf && p * p <= k)
+                        .all(|&p| !k.is_multiple_of(p)),
+                    "spf({k})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_sieve_small_limits() {
+        let empty = LinearSieve::new(0);
+        assert_eq!(empty.primes(), &[] as &[u64]);
+        assert_eq!(empty.totients(), &[0]);
+        let one = LinearSieve::new(1);
+        assert_eq!(one.mobius(), &[0, 1]);
+        let twelve = LinearSieve::new(12);
+        assert_eq!(twelve.smallest_prime_factors()[12], 2);
+        assert_eq!(twelve.smallest_prime_factors()[9], 3);
+        assert_eq!(twelve.totients()[12], 4);
+    }
+}
@@ -1,6 +1,56 @@
-use num_bigint::BigUint;
-use num_traits::One;
-use pyo3::{exceptions::PyValueError, prelude::*};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    hash::Hash,
+    io::{self, Write},
+    iter,
+    sync::{LazyLock, Mutex, MutexGuard},
+};
+#[cfg(feature = "python")]
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+#[cfg(feature = "python")]
+use pyo3::{
+    exceptions::{PyOverflowError, PyValueError},
+    prelude::*,
+};
+use rand::{Rng, RngCore, SeedableRng, rngs::StdRng};
+
+use crate::linear_sieve::LinearSieve;
+
+/// Errors reported by the math functions, mapped onto the matching Python exception when bound to Python
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    /// An argument outside the function's domain, raised as `ValueError`
+    Value(&'static str),
+    /// A result too large for its return type, raised as `OverflowError`
+    Overflow(&'static str),
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Value(message) | Self::Overflow(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for MathError {}
+
+#[cfg(feature = "python")]
+impl From<MathError> for PyErr {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::Value(message) => PyValueError::new_err(message),
+            MathError::Overflow(message) => PyOverflowError::new_err(message),
+        }
+    }
+}
+
+pub type MathResult<T> = Result<T, MathError>;
 
 fn factorial_u64(n: u64) -> u64 {
     if n == 0 {
@@ -37,10 +87,805 @@ fn product_range(end: u64, start: u64) -> BigUint {
     product
 }
 
-#[pyfunction]
-pub fn factorial(n: i64) -> PyResult<BigUint> {
+const SMALL_PRIME_LIMIT: usize = 10_000;
+const SMALL_PRIME_COUNT: usize = 1229;
+
+/// Every prime up to `SMALL_PRIME_LIMIT`, sieved at compile time
+static SMALL_PRIMES: [u64; SMALL_PRIME_COUNT] = small_primes();
+
+const fn small_primes() -> [u64; SMALL_PRIME_COUNT] {
+    let mut is_composite = [false; SMALL_PRIME_LIMIT + 1];
+    let mut primes = [0; SMALL_PRIME_COUNT];
+    let mut count = 0;
+    let mut i = 2;
+    while i <= SMALL_PRIME_LIMIT {
+        if !is_composite[i] {
+            primes[count] = i as u64;
+            count += 1;
+            let mut multiple = i * i;
+            while multiple <= SMALL_PRIME_LIMIT {
+                is_composite[multiple] = true;
+                multiple += i;
+            }
+        }
+        i += 1;
+    }
+    assert!(count == SMALL_PRIME_COUNT);
+    primes
+}
+
+/// Every prime `p <= n` in ascending order, served from `SMALL_PRIMES` when `n` is small enough
+///
+/// Limits up to `PRIME_CACHE_LIMIT` go through the shared cache; primes past it are sieved segment by
+/// segment for this call only, so the cache never holds more than the primes below `PRIME_CACHE_LIMIT`
+///
+/// # Panics
+///
+/// Panics if another thread panicked while holding the prime cache lock.
+pub fn primes_up_to(n: u64) -> Vec<u64> {
+    if n <= SMALL_PRIME_LIMIT as u64 {
+        let count = SMALL_PRIMES.partition_point(|&p| p <= n);
+        return SMALL_PRIMES[..count].to_vec();
+    }
+    let mut primes = PRIME_CACHE
+        .lock()
+        .expect("prime cache lock poisoned")
+        .primes_up_to(n.min(PRIME_CACHE_LIMIT));
+    if n > PRIME_CACHE_LIMIT {
+        primes.extend(prime_segments(PRIME_CACHE_LIMIT + 1, n).flatten());
+    }
+    primes
+}
+
+/// Every prime in `[min, max]` in ascending order, sieving only that window rather than everything below
+/// `max`
+#[must_use]
+pub fn primes_in_range(min: u64, max: u64) -> Vec<u64> {
+    prime_segments(min, max).flatten().collect()
+}
+
+/// Multiply `factors` pairwise, level by level, so each multiplication sees operands of similar size
+///
+/// Each pass halves the number of factors inside a loop rather than recursing, so stack usage stays
+/// constant however many factors there are
+pub fn product_tree(factors: &[BigUint]) -> BigUint {
+    let mut level: Vec<BigUint> = factors
+        .chunks(2)
+        .map(|pair| pair.iter().product())
+        .collect();
+    while level.len() > 1 {
+        level = level.chunks(2).map(|pair| pair.iter().product()).collect();
+    }
+    level.pop().unwrap_or_else(BigUint::one)
+}
+
+/// `product_tree` over machine words, pairing them up as u128 products before the first big multiply
+#[must_use]
+pub fn product_tree_u64(factors: &[u64]) -> BigUint {
+    let pairs: Vec<BigUint> = factors
+        .chunks(2)
+        .map(|pair| pair.iter().map(|&f| u128::from(f)).product::<u128>().into())
+        .collect();
+    product_tree(&pairs)
+}
+
+/// Sieve of Eratosthenes returning every prime `p <= n` in ascending order
+fn sieve_of_eratosthenes(n: u64) -> Vec<u64> {
+    let limit = usize::try_from(n).expect("sieve limit must fit in usize");
+    if limit < 2 {
+        return Vec::new();
+    }
+    let mut is_composite = vec![false; limit + 1];
+    let mut i = 2;
+    while i * i <= limit {
+        if !is_composite[i] {
+            for multiple in (i * i..=limit).step_by(i) {
+                is_composite[multiple] = true;
+            }
+        }
+        i += 1;
+    }
+    (2..=limit)
+        .filter(|&candidate| !is_composite[candidate])
+        .map(|prime| prime as u64)
+        .collect()
+}
+
+/// Composite flags for `[low, high]`, indexed from `low`, crossing off multiples of `base`, which must
+/// hold every prime up to `sqrt(high)`
+fn segment_composites(low: u64, high: u64, base: &[u64]) -> Vec<bool> {
+    if high < low {
+        return Vec::new();
+    }
+    let width = usize::try_from(high - low + 1).expect("sieve segment must fit in usize");
+    let mut is_composite = vec![false; width];
+    for &p in base {
+        let Some(first) = low.div_ceil(p).checked_mul(p) else {
+            continue;
+        };
+        let first = first.max(p * p);
+        for multiple in (first..=high).step_by(usize::try_from(p).expect("p is below sqrt(high)")) {
+            is_composite[usize::try_from(multiple - low).expect("offset is below width")] = true;
+        }
+    }
+    // 0 and 1 have no prime factor to cross them off
+    for small in low..2.min(high.saturating_add(1)) {
+        is_composite[usize::try_from(small - low).expect("offset is below width")] = true;
+    }
+    is_composite
+}
+
+/// The primes in `[low, high]`, crossing off multiples of `base`, which must hold every prime up to
+/// `sqrt(high)`
+fn sieve_segment(low: u64, high: u64, base: &[u64]) -> Vec<u64> {
+    segment_composites(low, high, base)
+        .into_iter()
+        .zip(low..=high)
+        .filter(|&(composite, _)| !composite)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Width of each window `prime_segments` sieves at once, which bounds its working memory
+const SEGMENT_WIDTH: u64 = 1 << 18;
+
+/// Largest `max` that `prime_segments` sieves; past it the base primes up to `sqrt(max)` would outgrow the
+/// prime cache, so each candidate is tested with `is_prime_u64` instead
+const SIEVED_RANGE_LIMIT: u64 = PRIME_CACHE_LIMIT * PRIME_CACHE_LIMIT;
+
+/// The `[low, high]` bounds of each `SEGMENT_WIDTH` window covering `[min.max(2), max]`, stepping with
+/// checked arithmetic so the last window can end at `u64::MAX`
+fn segment_windows(min: u64, max: u64) -> impl Iterator<Item = (u64, u64)> {
+    let mut next = Some(min.max(2)).filter(|&low| low <= max);
+    iter::from_fn(move || {
+        let low = next?;
+        let high = low.saturating_add(SEGMENT_WIDTH - 1).min(max);
+        next = high.checked_add(1).filter(|&low| low <= max);
+        Some((low, high))
+    })
+}
+
+/// The primes in `[min, max]` one `SEGMENT_WIDTH` window at a time, so memory stays bounded by the
+/// window and the primes up to `sqrt(max)` however far out the range lies
+fn prime_segments(min: u64, max: u64) -> impl Iterator<Item = Vec<u64>> {
+    let base = (max <= SIEVED_RANGE_LIMIT).then(|| primes_up_to(max.isqrt()));
+    segment_windows(min, max).map(move |(low, high)| match &base {
+        Some(base) => sieve_segment(low, high, base),
+        None => (low..=high).filter(|&n| is_prime_u64(n)).collect(),
+    })
+}
+
+/// Number of primes `p <= n`, counting each segment's survivors without collecting them
+fn count_primes_up_to(n: u64) -> u64 {
+    let base = primes_up_to(n.isqrt());
+    segment_windows(2, n)
+        .map(|(low, high)| {
+            segment_composites(low, high, &base)
+                .into_iter()
+                .filter(|&composite| !composite)
+                .count() as u64
+        })
+        .sum()
+}
+
+/// Highest limit whose primes `primes_up_to` keeps in `PRIME_CACHE` between calls
+const PRIME_CACHE_LIMIT: u64 = 1 << 24;
+
+/// Primes up to `limit`, grown by sieving only the segment past the previous limit
+struct PrimeCache {
+    limit: u64,
+    primes: Vec<u64>,
+    sieve_passes: usize,
+}
+
+impl PrimeCache {
+    const fn new() -> Self {
+        Self {
+            limit: 1,
+            primes: Vec::new(),
+            sieve_passes: 0,
+        }
+    }
+
+    fn extend_to(&mut self, limit: u64) {
+        if limit <= self.limit {
+            return;
+        }
+        let low = self.limit + 1;
+        let root = limit.isqrt();
+        let base = if root <= self.limit {
+            let count = self.primes.partition_point(|&p| p <= root);
+            self.primes[..count].to_vec()
+        } else {
+            sieve_of_eratosthenes(root)
+        };
+        self.primes.extend(sieve_segment(low, limit, &base));
+        self.limit = limit;
+        self.sieve_passes += 1;
+    }
+
+    fn primes_up_to(&mut self, n: u64) -> Vec<u64> {
+        self.extend_to(n);
+        let count = self.primes.partition_point(|&p| p <= n);
+        self.primes[..count].to_vec()
+    }
+}
+
+static PRIME_CACHE: Mutex<PrimeCache> = Mutex::new(PrimeCache::new());
+
+/// `a * b mod m` computed through a u128 so the product cannot overflow
+#[allow(clippy::cast_possible_truncation)]
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (u128::from(a) * u128::from(b) % u128::from(m)) as u64
+}
+
+/// `base^exp mod m` by square-and-multiply
+fn pow_mod(base: u64, exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Prime factorization of `n` as ascending `(prime, exponent)` pairs
+///
+/// Divides by `SMALL_PRIMES` first, stopping early once the divisor passes the square root of what
+/// remains. A cofactor left over after the whole table has no factor below `SMALL_PRIME_LIMIT`, so it is
+/// split with Pollard's rho instead of continuing trial division toward its square root
+fn prime_factorization(n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    let mut remaining = n;
+    for &divisor in &SMALL_PRIMES {
+        if divisor > remaining / divisor {
+            break;
+        }
+        let mut exponent = 0;
+        while remaining.is_multiple_of(divisor) {
+            remaining /= divisor;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((divisor, exponent));
+        }
+    }
+    if remaining > 1 {
+        let mut large = Vec::new();
+        rho_prime_factors(remaining, &mut large);
+        large.sort_unstable();
+        for p in large {
+            match factors.last_mut() {
+                Some((last, exponent)) if *last == p => *exponent += 1,
+                _ => factors.push((p, 1)),
+            }
+        }
+    }
+    factors
+}
+
+/// A nontrivial factor of the composite `n`, by Pollard's rho with Brent's cycle detection
+///
+/// `|x - y|` values are multiplied together and only every `BATCH` steps is a gcd taken; if a batch
+/// overshoots to a gcd of `n`, the last batch is replayed one step at a time, and if that still fails the
+/// walk restarts with a new constant in `x^2 + c`
+#[allow(clippy::cast_possible_truncation)]
+fn pollard_rho_brent(n: u64) -> u64 {
+    const BATCH: u64 = 128;
+    if n.is_multiple_of(2) {
+        return 2;
+    }
+    for c in 1..n {
+        let step = |x: u64| ((u128::from(mul_mod(x, x, n)) + u128::from(c)) % u128::from(n)) as u64;
+        let (mut x, mut y, mut saved) = (0, 2, 2);
+        let (mut product, mut divisor, mut cycle) = (1, 1, 1);
+        while divisor == 1 {
+            x = y;
+            for _ in 0..cycle {
+                y = step(y);
+            }
+            let mut walked = 0;
+            while walked < cycle && divisor == 1 {
+                saved = y;
+                for _ in 0..BATCH.min(cycle - walked) {
+                    y = step(y);
+                    product = mul_mod(product, x.abs_diff(y), n);
+                }
+                divisor = product.gcd(&n);
+                walked += BATCH;
+            }
+            cycle *= 2;
+        }
+        if divisor == n {
+            loop {
+                saved = step(saved);
+                divisor = x.abs_diff(saved).gcd(&n);
+                if divisor > 1 {
+                    break;
+                }
+            }
+        }
+        if divisor != n {
+            return divisor;
+        }
+    }
+    unreachable!("{n} is composite, so some constant yields a factor")
+}
+
+/// Push every prime factor of `n` onto `factors`, with multiplicity and in no particular order, splitting
+/// composites with `pollard_rho_brent` until each piece passes `is_prime_u64`
+fn rho_prime_factors(n: u64, factors: &mut Vec<u64>) {
+    let mut pending = vec![n];
+    while let Some(m) = pending.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime_u64(m) {
+            factors.push(m);
+            continue;
+        }
+        let divisor = pollard_rho_brent(m);
+        pending.push(divisor);
+        pending.push(m / divisor);
+    }
+}
+
+/// The prime `p` when `n = p^k` for some `k >= 1`
+fn prime_power_base(n: u64) -> Option<u64> {
+    match prime_factorization(n).as_slice() {
+        &[(p, _)] => Some(p),
+        _ => None,
+    }
+}
+
+/// `sigma(n)`, the sum of every divisor of `n`, as `prod (p^(e+1) - 1) / (p - 1)` over its factorization.
+/// A u128 holds the result because `p^(e+1)` exceeds `n` by at most a factor of `p`
+fn sum_divisors(n: u64) -> u128 {
+    prime_factorization(n)
+        .into_iter()
+        .map(|(p, exponent)| {
+            let p = u128::from(p);
+            (p.pow(exponent + 1) - 1) / (p - 1)
+        })
+        .product()
+}
+
+/// The smallest `k >= 1` with `a^k ≡ 1 (mod m)`, or `None` when `a` is not a unit mod `m`
+///
+/// Starts from `phi(m)`, which the order divides, and strips each prime factor `q` while `a^(order / q)`
+/// is still 1
+fn multiplicative_order(a: u64, m: u64) -> Option<u64> {
+    if m <= 1 || a.gcd(&m) != 1 {
+        return None;
+    }
+    let phi = totient(m).expect("m is above 1");
+    let mut order = phi;
+    for (q, _) in prime_factorization(phi) {
+        while order.is_multiple_of(q) && pow_mod(a, order / q, m) == 1 {
+            order /= q;
+        }
+    }
+    Some(order)
+}
+
+/// Primes in `[min, max]` paired with their 1-based index among all primes
+fn indexed_primes(min: u64, max: u64) -> impl Iterator<Item = (u64, u64)> {
+    let below = count_primes_up_to(min.saturating_sub(1));
+    primes_in_range(min, max)
+        .into_iter()
+        .zip(below + 1..)
+        .map(|(p, index)| (index, p))
+}
+
+/// Stream the primes in `[min, max]` to `out` as decimal text joined by `sep`
+///
+/// Each `SEGMENT_WIDTH` window is sieved and written before the next, so only one window's primes are held
+/// at a time
+///
+/// # Errors
+///
+/// Returns any I/O error from writing to or flushing `out`.
+pub fn write_primes_to(min: u64, max: u64, out: &mut dyn Write, sep: &str) -> io::Result<()> {
+    let mut first = true;
+    for segment in prime_segments(min, max) {
+        for p in segment {
+            if !first {
+                out.write_all(sep.as_bytes())?;
+            }
+            first = false;
+            write!(out, "{p}")?;
+        }
+    }
+    out.flush()
+}
+
+/// Call `visitor` with each prime `p` in `[min, max]` satisfying `p ≡ a (mod m)`, in ascending order
+fn iterate_primes_congruent(min: u64, max: u64, a: u64, m: u64, visitor: impl FnMut(u64)) {
+    let residue = a % m;
+    prime_segments(min, max)
+        .flatten()
+        .filter(|&p| p % m == residue)
+        .for_each(visitor);
+}
+
+/// Call `visitor` with each prime in `[min, max]`, from `max` down to `min`
+pub fn iterate_primes_rev(min: u64, max: u64, visitor: impl FnMut(u64)) {
+    primes_in_range(min, max)
+        .into_iter()
+        .rev()
+        .for_each(visitor);
+}
+
+/// Each prime in `[min, max]` paired with the sum of the primes in `[min, p]`
+pub fn cumulative_prime_sums(min: u64, max: u64) -> impl Iterator<Item = (u64, BigUint)> {
+    let mut running_sum = BigUint::ZERO;
+    prime_segments(min, max).flatten().map(move |p| {
+        running_sum += p;
+        (p, running_sum.clone())
+    })
+}
+
+/// Call `visitor` with each Sophie Germain prime `p` in `[min, max]`, those for which `2p + 1` is also
+/// prime, in ascending order
+pub fn iterate_sophie_germain(min: u64, max: u64, visitor: impl FnMut(u64)) {
+    prime_segments(min, max)
+        .flatten()
+        .filter(|&p| {
+            p.checked_mul(2)
+                .and_then(|doubled| doubled.checked_add(1))
+                .is_some_and(is_prime_u64)
+        })
+        .for_each(visitor);
+}
+
+/// Call `visitor` with the first member `p` of each prime quadruplet `{p, p + 2, p + 6, p + 8}` lying
+/// entirely within `[min, max]`, in ascending order
+pub fn iterate_prime_quadruplets(min: u64, max: u64, visitor: impl FnMut(u64)) {
+    let primes = primes_in_range(min, max);
+    primes
+        .iter()
+        .copied()
+        .filter(|&p| {
+            [2, 6, 8]
+                .iter()
+                .all(|&offset| primes.binary_search(&(p + offset)).is_ok())
+        })
+        .for_each(visitor);
+}
+
+/// Call `visitor` with each prime in `[min, max]` whose decimal digits, most significant first and as
+/// values `0..=9`, satisfy `predicate`
+pub fn iterate_primes_matching(
+    min: u64,
+    max: u64,
+    predicate: impl Fn(&[u8]) -> bool,
+    mut visitor: impl FnMut(u64),
+) {
+    for p in prime_segments(min, max).flatten() {
+        let digits: Vec<u8> = p.to_string().bytes().map(|digit| digit - b'0').collect();
+        if predicate(&digits) {
+            visitor(p);
+        }
+    }
+}
+
+/// Exponent of the prime `p` in `comb(n, k)` for `k <= n`: by Kummer's theorem, the number of carries
+/// when adding `k` and `n - k` in base `p`
+fn kummer_valuation(n: u64, k: u64, p: u64) -> u64 {
+    let (mut left, mut right) = (k, n - k);
+    let (mut carry, mut count) = (0, 0);
+    while left > 0 || right > 0 || carry > 0 {
+        carry = u64::from(left % p + right % p + carry >= p);
+        count += carry;
+        left /= p;
+        right /= p;
+    }
+    count
+}
+
+/// Legendre's formula for the exponent of the prime `p` in `n!`
+fn legendre_valuation(n: u64, p: u64) -> u64 {
+    let mut valuation = 0;
+    let mut quotient = n;
+    while quotient > 0 {
+        quotient /= p;
+        valuation += quotient;
+    }
+    valuation
+}
+
+/// Pick a uniformly random prime in `[low, high]` by counting the primes in range and drawing an index
+pub fn random_prime_with_rng<R: RngCore + ?Sized>(low: u64, high: u64, rng: &mut R) -> Option<u64> {
+    let candidates = primes_in_range(low, high);
+    if candidates.is_empty() {
+        None
+    } else {
+        Some(candidates[rng.random_range(0..candidates.len())])
+    }
+}
+
+const MILLER_RABIN_BASES: [u64; 20] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+];
+
+/// Trial division by `SMALL_PRIMES` followed by Miller-Rabin over `MILLER_RABIN_BASES`
+fn is_probable_prime(n: &BigUint) -> bool {
+    for &p in &SMALL_PRIMES {
+        if *n == BigUint::from(p) {
+            return true;
+        }
+        if (n % p).is_zero() {
+            return false;
+        }
+    }
+    if *n < BigUint::from(SMALL_PRIME_LIMIT * SMALL_PRIME_LIMIT) {
+        return *n > BigUint::one();
+    }
+    let n_minus_one = n - 1_u32;
+    let twos = n_minus_one.trailing_zeros().unwrap_or(0);
+    let odd_part = &n_minus_one >> twos;
+    'witness: for base in MILLER_RABIN_BASES {
+        let mut x = BigUint::from(base).modpow(&odd_part, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..twos {
+            x = &x * &x % n;
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Draw random odd `bits`-bit candidates from `rng` until one passes `is_probable_prime`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `bits` is below 2.
+pub fn prime_with_bit_length_with_rng<R: RngCore + ?Sized>(
+    bits: u32,
+    rng: &mut R,
+) -> MathResult<BigUint> {
+    if bits < 2 {
+        return Err(MathError::Value(
+            "prime_with_bit_length() bit length must be at least 2",
+        ));
+    }
+    let top_bit = u64::from(bits - 1);
+    let mut bytes = vec![0; bits.div_ceil(8) as usize];
+    loop {
+        rng.fill_bytes(&mut bytes);
+        let mut candidate =
+            BigUint::from_bytes_le(&bytes) >> (bytes.len() as u64 * 8 - u64::from(bits));
+        candidate.set_bit(top_bit, true);
+        candidate.set_bit(0, true);
+        if is_probable_prime(&candidate) {
+            return Ok(candidate);
+        }
+    }
+}
+
+/// Multiply primes `p <= smoothness` drawn uniformly with `rng` until the product has at least `bits` bits
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `smoothness` is below 2, leaving no prime to multiply.
+pub fn random_smooth_number_with_rng<R: RngCore + ?Sized>(
+    bits: u32,
+    smoothness: u64,
+    rng: &mut R,
+) -> MathResult<BigUint> {
+    let primes = primes_up_to(smoothness);
+    if primes.is_empty() {
+        return Err(MathError::Value(
+            "random_smooth_number() smoothness must be at least 2",
+        ));
+    }
+    let mut product = BigUint::one();
+    while product.bits() < u64::from(bits) {
+        product *= primes[rng.random_range(0..primes.len())];
+    }
+    Ok(product)
+}
+
+/// Deterministic primality test for any u64, reporting each step to `log`
+///
+/// Trial division by `SMALL_PRIMES` settles every `n` below `SMALL_PRIME_LIMIT^2`; larger `n` go through
+/// Miller-Rabin with the first twelve prime bases, which has no counterexamples below 2^64
+fn check_prime_u64(n: u64, log: &mut dyn FnMut(fmt::Arguments<'_>)) -> bool {
+    if n < 2 {
+        log(format_args!("{n} is less than 2, so it is not prime"));
+        return false;
+    }
+    for &p in &SMALL_PRIMES {
+        if p * p > n {
+            log(format_args!(
+                "no prime up to isqrt({n}) divides {n}, so it is prime"
+            ));
+            return true;
+        }
+        if n.is_multiple_of(p) {
+            log(format_args!("{p} divides {n}, so it is composite"));
+            return false;
+        }
+        log(format_args!("{p} does not divide {n}"));
+    }
+    let twos = (n - 1).trailing_zeros();
+    let odd_part = (n - 1) >> twos;
+    log(format_args!("{n} - 1 = 2^{twos} * {odd_part}"));
+    'witness: for base in &MILLER_RABIN_BASES[..12] {
+        let mut x = pow_mod(*base, odd_part, n);
+        if x == 1 || x == n - 1 {
+            log(format_args!(
+                "base {base}: {base}^{odd_part} mod {n} = {x}, round passed"
+            ));
+            continue;
+        }
+        for squarings in 1..twos {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                log(format_args!(
+                    "base {base}: reached {n} - 1 after {squarings} squarings, round passed"
+                ));
+                continue 'witness;
+            }
+        }
+        log(format_args!(
+            "base {base} is a Miller-Rabin witness, so {n} is composite"
+        ));
+        return false;
+    }
+    log(format_args!(
+        "{n} passed every Miller-Rabin round, so it is prime"
+    ));
+    true
+}
+
+fn is_prime_u64(n: u64) -> bool {
+    check_prime_u64(n, &mut |_| {})
+}
+
+/// Greatest common divisor of every value, which is 0 for an empty input
+pub fn gcd_all(values: impl IntoIterator<Item = BigInt>) -> BigInt {
+    values
+        .into_iter()
+        .fold(BigInt::ZERO, |divisor, value| divisor.gcd(&value))
+}
+
+/// The exact value of a finite float as a reduced fraction, like Python's `float.as_integer_ratio`
+fn float_as_ratio(x: f64) -> (BigInt, BigInt) {
+    const MANTISSA_BITS: u32 = 52;
+    let bits = x.to_bits();
+    let biased_exponent = i64::try_from((bits >> MANTISSA_BITS) & 0x7ff).expect("11-bit exponent");
+    let fraction = bits & ((1 << MANTISSA_BITS) - 1);
+    let (mantissa, exponent) = if biased_exponent == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << MANTISSA_BITS), biased_exponent - 1075)
+    };
+    let mut numerator = BigInt::from(mantissa);
+    if x.is_sign_negative() {
+        numerator = -numerator;
+    }
+    let shift = exponent.unsigned_abs();
+    if exponent >= 0 {
+        return (numerator << shift, BigInt::one());
+    }
+    let denominator = BigInt::one() << shift;
+    let divisor = numerator.gcd(&denominator);
+    (numerator / &divisor, denominator / divisor)
+}
+
+fn lgamma(x: f64) -> f64 {
+    x.ln_gamma().0
+}
+
+const MATH_CACHE_CAPACITY: usize = 256;
+/// Largest result, in bits, that `cached` keeps; bigger ones are recomputed rather than pinning megabytes
+/// per entry
+const MATH_CACHE_MAX_BITS: u64 = 1 << 20;
+
+/// Bounded map that evicts the least recently used entry once `capacity` is reached
+struct LruCache<K, V> {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<K, (u64, V)>,
+}
+
+impl<K: Eq + Hash + Copy, V: Clone> LruCache<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::with_capacity(capacity),
+        }
+    }
+
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let (last_used, value) = self.entries.get_mut(key)?;
+        *last_used = self.tick;
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let least_recent = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(key, _)| *key);
+            if let Some(least_recent) = least_recent {
+                self.entries.remove(&least_recent);
+            }
+        }
+        self.tick += 1;
+        self.entries.insert(key, (self.tick, value));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+type MathCache<K> = LazyLock<Mutex<LruCache<K, BigUint>>>;
+
+static FACTORIAL_CACHE: MathCache<i64> =
+    LazyLock::new(|| Mutex::new(LruCache::new(MATH_CACHE_CAPACITY)));
+static COMB_CACHE: MathCache<(i64, i64)> =
+    LazyLock::new(|| Mutex::new(LruCache::new(MATH_CACHE_CAPACITY)));
+static ACKERMANN_CACHE: MathCache<(u64, u64)> =
+    LazyLock::new(|| Mutex::new(LruCache::new(MATH_CACHE_CAPACITY)));
+
+fn lock_cache<K>(cache: &MathCache<K>) -> MutexGuard<'_, LruCache<K, BigUint>> {
+    cache.lock().expect("math cache lock poisoned")
+}
+
+/// Serve `key` from `cache`, computing and storing it on a miss unless it is over `MATH_CACHE_MAX_BITS`;
+/// the lock is not held while computing
+fn cached<K: Eq + Hash + Copy>(
+    cache: &MathCache<K>,
+    key: K,
+    compute: impl FnOnce() -> MathResult<BigUint>,
+) -> MathResult<BigUint> {
+    if let Some(value) = lock_cache(cache).get(&key) {
+        return Ok(value);
+    }
+    let value = compute()?;
+    if value.bits() <= MATH_CACHE_MAX_BITS {
+        lock_cache(cache).insert(key, value.clone());
+    }
+    Ok(value)
+}
+
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn clear_math_cache() {
+    lock_cache(&FACTORIAL_CACHE).clear();
+    lock_cache(&COMB_CACHE).clear();
+    lock_cache(&ACKERMANN_CACHE).clear();
+}
+
+/// `n!`, served from the factorial cache when it was computed recently
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is negative.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn factorial(n: i64) -> MathResult<BigUint> {
+    cached(&FACTORIAL_CACHE, n, || factorial_uncached(n))
+}
+
+fn factorial_uncached(n: i64) -> MathResult<BigUint> {
     if n < 0 {
-        Err(PyValueError::new_err(
+        Err(MathError::Value(
             "factorial() not defined for negative values",
         ))
     } else if n < 21 {
@@ -52,43 +897,57 @@ pub fn factorial(n: i64) -> PyResult<BigUint> {
     }
 }
 
-#[pyfunction]
-pub fn comb(n: i64, k: i64) -> PyResult<BigUint> {
+/// Number of ways to choose `k` of `n` items, served from the comb cache when it was computed recently
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` or `k` is negative.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn comb(n: i64, k: i64) -> MathResult<BigUint> {
+    cached(&COMB_CACHE, (n, k), || comb_uncached(n, k))
+}
+
+fn comb_uncached(n: i64, k: i64) -> MathResult<BigUint> {
     if n < 0 {
-        Err(PyValueError::new_err(
+        Err(MathError::Value(
             "factorial() not defined for negative values",
         ))
+    } else if k < 0 {
+        Err(MathError::Value("k must be a non-negative integer"))
     } else if k > n {
         Ok(BigUint::ZERO)
     } else {
-        let k = k.min(n - k);
+        let k = k.min(n - k) as u64;
         let n = n as u64;
-        Ok(product_range(n - k as u64, n) / factorial(k).unwrap())
+        Ok(product_range(n, n - k + 1) / factorial_biguint(k))
     }
 }
 
 /// Use Newton-Raphson algorithm to compute isqrt
-#[pyfunction]
-pub fn isqrt(n: i64) -> PyResult<i64> {
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is negative.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn isqrt(n: i64) -> MathResult<i64> {
     if n < 0 {
-        return Err(PyValueError::new_err(
-            "isqrt() argument must be nonnegative",
-        ));
+        return Err(MathError::Value("isqrt() argument must be nonnegative"));
     }
     if n < 2 {
         return Ok(n);
     }
     let mut x0 = n / 2;
-    let mut x1 = (x0 + n / x0) / 2;
+    let mut x1 = i64::midpoint(x0, n / x0);
     while x1 < x0 {
         x0 = x1;
-        x1 = (x0 + n / x0) / 2;
+        x1 = i64::midpoint(x0, n / x0);
     }
     Ok(x0)
 }
 
-#[pyfunction]
-#[pyo3(signature = (n, k=None))]
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (n, k=None)))]
+#[must_use]
 pub fn perm(n: i64, k: Option<i64>) -> BigUint {
     let n = n as u64;
     match k {
@@ -104,33 +963,3902 @@ pub fn perm(n: i64, k: Option<i64>) -> BigUint {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    extern crate test;
-    use test::Bencher;
+/// Build `n!` as the product of `p^e` over primes `p <= n`, where each `e` comes from Legendre's formula
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is negative.
+///
+/// # Panics
+///
+/// Panics if an exponent of `n!` exceeds `u32::MAX`, which takes `n` above 2^32.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn primorial_power(n: i64) -> MathResult<BigUint> {
+    if n < 0 {
+        return Err(MathError::Value(
+            "primorial_power() not defined for negative values",
+        ));
+    }
+    let prime_powers: Vec<BigUint> = factorial_factorization(n as u64)
+        .into_iter()
+        .map(|(p, exponent)| {
+            let exponent = u32::try_from(exponent).expect("factorial exponent must fit in u32");
+            BigUint::from(p).pow(exponent)
+        })
+        .collect();
+    Ok(product_tree(&prime_powers))
+}
 
-    #[test]
-    fn test_isqrt() {
-        for i in 4..10000 {
-            let square: i64 = i * i;
-            if let Ok(less_result) = isqrt(square - 1) {
-                assert_eq!(
-                    less_result,
-                    i - 1,
-                    "one less of square {} should be {}",
-                    less_result,
-                    i - 1
-                );
-            }
-            assert_eq!(isqrt(square).unwrap(), i);
-            if let Ok(greater_result) = isqrt(square + 1) {
-                assert_eq!(
-                    greater_result, i,
-                    "one greater of square {greater_result} should be {i}",
-                );
-            }
-        }
+/// Each prime `p <= n` paired with its exponent in `n!`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn factorial_factorization(n: u64) -> Vec<(u64, u64)> {
+    primes_up_to(n)
+        .into_iter()
+        .map(|p| (p, legendre_valuation(n, p)))
+        .collect()
+}
+
+/// A uniformly random prime in `[low, high]`, reproducible when `seed` is given
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `[low, high]` contains no prime.
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (low, high, seed=None)))]
+pub fn random_prime(low: u64, high: u64, seed: Option<u64>) -> MathResult<u64> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    random_prime_with_rng(low, high, &mut rng)
+        .ok_or(MathError::Value("random_prime() found no prime in range"))
+}
+
+/// Binomial probability mass evaluated in log-space so large `n` does not overflow
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `p` is outside `[0, 1]`.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss)]
+pub fn binomial_pmf(n: u64, k: u64, p: f64) -> MathResult<f64> {
+    if !(0.0..=1.0).contains(&p) {
+        return Err(MathError::Value(
+            "binomial_pmf() probability must be between 0 and 1",
+        ));
+    }
+    if k > n {
+        return Ok(0.0);
+    }
+    if p <= 0.0 {
+        return Ok(if k == 0 { 1.0 } else { 0.0 });
+    }
+    if p >= 1.0 {
+        return Ok(if k == n { 1.0 } else { 0.0 });
+    }
+    let (n, k) = (n as f64, k as f64);
+    let ln_comb = lgamma(n + 1.0) - lgamma(k + 1.0) - lgamma(n - k + 1.0);
+    Ok((ln_comb + k * p.ln() + (n - k) * (-p).ln_1p()).exp())
+}
+
+/// Exact probability of `k` successes in `n` trials with success probability `num / den`, as a reduced fraction
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `p` is outside `[0, 1]`.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn binomial_probability_exact(
+    n: u32,
+    k: u32,
+    num: u64,
+    den: u64,
+) -> MathResult<(BigUint, BigUint)> {
+    if den == 0 || num > den {
+        return Err(MathError::Value(
+            "binomial_probability_exact() probability must be between 0 and 1",
+        ));
+    }
+    let numerator = comb(i64::from(n), i64::from(k))?
+        * BigUint::from(num).pow(k)
+        * BigUint::from(den - num).pow(n.saturating_sub(k));
+    let denominator = BigUint::from(den).pow(n);
+    let divisor = numerator.gcd(&denominator);
+    Ok((numerator / &divisor, denominator / divisor))
+}
+
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn enumerate_primes(min: u64, max: u64) -> Vec<(u64, u64)> {
+    indexed_primes(min, max).collect()
+}
+
+/// Product of all primes `p <= n` reduced modulo `m`, without materialising the full primorial
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `m` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn primorial_mod(n: u64, m: u64) -> MathResult<u64> {
+    if m == 0 {
+        return Err(MathError::Value("primorial_mod() modulus must be positive"));
+    }
+    let mut product = 1 % m;
+    for p in primes_up_to(n) {
+        product = mul_mod(product, p, m);
+    }
+    Ok(product)
+}
+
+/// Floating-point estimate of `comb(n, k)` from log-gamma, for when only the magnitude matters
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn comb_estimate(n: u64, k: u64) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    let (n, k) = (n as f64, k as f64);
+    (lgamma(n + 1.0) - lgamma(k + 1.0) - lgamma(n - k + 1.0)).exp()
+}
+
+/// Number of twin prime pairs `(p, p + 2)` with both members in `[low, high]`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn twin_prime_count(low: u64, high: u64) -> usize {
+    primes_in_range(low, high)
+        .windows(2)
+        .filter(|pair| pair[1] - pair[0] == 2)
+        .count()
+}
+
+/// Machine-word `base^exp`, raising `OverflowError` rather than promoting to a big integer
+///
+/// # Errors
+///
+/// Returns [`MathError::Overflow`] if `base^exp` does not fit in a u64.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn ipow_checked(base: u64, exp: u32) -> MathResult<u64> {
+    base.checked_pow(exp).ok_or(MathError::Overflow(
+        "ipow_checked() result does not fit in 64 bits",
+    ))
+}
+
+/// The first `k` primes, sieving up to the prime number theorem bound `k (ln k + ln ln k)`
+///
+/// # Panics
+///
+/// Panics if `k` does not fit in a `usize`.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+#[must_use]
+pub fn first_n_primes(k: u64) -> Vec<u64> {
+    let count = usize::try_from(k).expect("prime count must fit in usize");
+    // The bound only holds from k = 6 onwards; the fifth prime is 11
+    let mut limit = if k < 6 {
+        11
+    } else {
+        let k = k as f64;
+        (k * (k.ln() + k.ln().ln())).ceil() as u64
+    };
+    let mut primes = primes_up_to(limit);
+    while primes.len() < count {
+        limit *= 2;
+        primes = primes_up_to(limit);
+    }
+    primes.truncate(count);
+    primes
+}
+
+/// A random probable prime with exactly `bits` bits
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `bits` is below 2.
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (bits, seed=None)))]
+pub fn prime_with_bit_length(bits: u32, seed: Option<u64>) -> MathResult<BigUint> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    prime_with_bit_length_with_rng(bits, &mut rng)
+}
+
+/// Primality of `n` together with a human-readable log of every check performed
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn is_prime_trace(n: u64) -> (bool, Vec<String>) {
+    let mut trace = Vec::new();
+    let is_prime = check_prime_u64(n, &mut |step| trace.push(step.to_string()));
+    (is_prime, trace)
+}
+
+/// Count the primes in `[low, high]` by their final decimal digit
+fn primes_by_last_digit(low: u64, high: u64) -> [usize; 10] {
+    let mut counts = [0; 10];
+    for p in prime_segments(low, high).flatten() {
+        counts[(p % 10) as usize] += 1;
+    }
+    counts
+}
+
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn count_primes_by_last_digit(low: u64, high: u64) -> BTreeMap<usize, usize> {
+    primes_by_last_digit(low, high)
+        .into_iter()
+        .enumerate()
+        .collect()
+}
+
+/// Product of the primes in `[lo, hi]`, multiplied with `product_tree_u64` so the operands stay balanced
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn primorial_iterative(lo: u64, hi: u64) -> BigUint {
+    product_tree_u64(&primes_in_range(lo, hi))
+}
+
+/// Multiply arbitrarily many integers with a balanced product tree
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "product_tree")]
+#[allow(clippy::needless_pass_by_value)]
+#[must_use]
+pub fn product_tree_py(factors: Vec<BigUint>) -> BigUint {
+    product_tree(&factors)
+}
+
+/// Korselt's criterion: `n` is composite, squarefree, and `p - 1` divides `n - 1` for every prime `p | n`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn is_carmichael(n: u64) -> bool {
+    let factors = prime_factorization(n);
+    factors.len() > 1
+        && factors
+            .iter()
+            .all(|&(p, exponent)| exponent == 1 && (n - 1).is_multiple_of(p - 1))
+}
+
+/// Exact sum of `1/p` over primes `p <= n` as a reduced fraction, using the primorial as common denominator
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn sum_reciprocal_primes_exact(n: u64) -> (BigUint, BigUint) {
+    let denominator = primorial_iterative(0, n);
+    let numerator: BigUint = primes_up_to(n).into_iter().map(|p| &denominator / p).sum();
+    let divisor = numerator.gcd(&denominator);
+    (numerator / &divisor, denominator / divisor)
+}
+
+/// Approximate `zeta(s)` for `s > 1` by the Euler product of `1 / (1 - p^-s)` over primes `p <= prime_limit`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] unless `s > 1`, where the product converges.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss)]
+pub fn zeta_euler_product(s: f64, prime_limit: u64) -> MathResult<f64> {
+    if s.is_nan() || s <= 1.0 {
+        return Err(MathError::Value("zeta_euler_product() requires s > 1"));
+    }
+    Ok(primes_up_to(prime_limit)
+        .into_iter()
+        .map(|p| 1.0 / (1.0 - (p as f64).powf(-s)))
+        .product())
+}
+
+/// `(-1)^k` where `k` counts the prime factors of `n` with multiplicity
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn liouville(n: u64) -> MathResult<i8> {
+    if n == 0 {
+        return Err(MathError::Value("liouville() not defined for 0"));
+    }
+    let total: u32 = prime_factorization(n)
+        .into_iter()
+        .map(|(_, exponent)| exponent)
+        .sum();
+    Ok(if total.is_multiple_of(2) { 1 } else { -1 })
+}
+
+/// Sum of `liouville(k)` for `1 <= k <= n`, counting prime factors for the whole range with one sieve pass
+///
+/// # Panics
+///
+/// Panics if `n` does not fit in a `usize`.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_possible_truncation)]
+#[must_use]
+pub fn liouville_sum(n: u64) -> i64 {
+    let limit = usize::try_from(n).expect("liouville_sum() limit must fit in usize");
+    let mut factor_count = vec![0_u8; limit + 1];
+    for p in primes_up_to(n) {
+        let mut power = p;
+        loop {
+            for multiple in (power..=n).step_by(power as usize) {
+                factor_count[multiple as usize] += 1;
+            }
+            match power.checked_mul(p) {
+                Some(next) if next <= n => power = next,
+                _ => break,
+            }
+        }
+    }
+    factor_count
+        .iter()
+        .skip(1)
+        .map(|&count| if count.is_multiple_of(2) { 1 } else { -1 })
+        .sum()
+}
+
+/// `n!` as a float, exact for `n <= 20` and from log-gamma beyond, overflowing to `inf` past `170!`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is negative.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss)]
+pub fn factorial_float(n: i64) -> MathResult<f64> {
+    if n < 0 {
+        Err(MathError::Value(
+            "factorial_float() not defined for negative values",
+        ))
+    } else if n < 21 {
+        Ok(factorial_u64(n as u64) as f64)
+    } else {
+        Ok(lgamma(n as f64 + 1.0).exp())
+    }
+}
+
+/// Greatest common divisor of the integers produced by any Python iterable
+///
+/// # Errors
+///
+/// Returns `TypeError` if `iterable` is not iterable or yields a non-integer.
+#[cfg(feature = "python")]
+#[pyfunction]
+pub fn gcd_iter(iterable: &Bound<'_, PyAny>) -> PyResult<BigInt> {
+    let values = iterable
+        .try_iter()?
+        .map(|item| item?.extract::<BigInt>())
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(gcd_all(values))
+}
+
+/// First prime `p > start` whose gap to the next prime is at least `g`, or `None` when no such gap closes
+/// at or below `limit`
+///
+/// Windows past `start` double in width and are sieved segment by segment, so memory stays bounded by the
+/// segment and the primes up to `sqrt(limit)` rather than growing with the search
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (start, g, limit=u64::MAX)))]
+#[must_use]
+pub fn next_gap_at_least(start: u64, g: u64, limit: u64) -> Option<u64> {
+    let mut previous: Option<u64> = None;
+    let mut low = start.checked_add(1)?;
+    let mut width = start.max(1024);
+    while low <= limit {
+        let high = low.saturating_add(width).min(limit);
+        for p in prime_segments(low, high).flatten() {
+            if let Some(q) = previous
+                && p - q >= g
+            {
+                return Some(q);
+            }
+            previous = Some(p);
+        }
+        low = high.checked_add(1)?;
+        width = width.saturating_mul(2);
+    }
+    None
+}
+
+/// Largest `n` with `n! <= x`, found by multiplying up the factorials until one exceeds `x`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `x` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn inverse_factorial(x: BigUint) -> MathResult<u64> {
+    if x.is_zero() {
+        return Err(MathError::Value(
+            "inverse_factorial() argument must be positive",
+        ));
+    }
+    let mut n = 0;
+    let mut next_factorial = BigUint::one();
+    while next_factorial <= x {
+        n += 1;
+        next_factorial *= n + 1;
+    }
+    Ok(n)
+}
+
+/// Write the primes in `[min, max]` straight to the file at `path` without building Python objects
+///
+/// # Errors
+///
+/// Returns `OSError` if `path` cannot be created or written.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(signature = (min, max, path, sep="\n"))]
+#[allow(clippy::needless_pass_by_value)]
+pub fn write_primes(min: u64, max: u64, path: PathBuf, sep: &str) -> PyResult<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_primes_to(min, max, &mut out, sep)?;
+    Ok(())
+}
+
+/// The von Mangoldt function: `ln p` when `n` is a power of the prime `p`, otherwise 0
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss)]
+pub fn von_mangoldt(n: u64) -> MathResult<f64> {
+    if n == 0 {
+        return Err(MathError::Value("von_mangoldt() not defined for 0"));
+    }
+    Ok(prime_power_base(n).map_or(0.0, |p| (p as f64).ln()))
+}
+
+/// Second Chebyshev function: the sum of `von_mangoldt(n)` for `n <= x`
+///
+/// Each prime `p <= x` contributes `ln p` once for every power `p^k <= x`
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn chebyshev_psi(x: u64) -> f64 {
+    primes_up_to(x)
+        .into_iter()
+        .map(|p| {
+            let mut powers = 0;
+            let mut power = 1_u64;
+            while let Some(next) = power.checked_mul(p).filter(|&next| next <= x) {
+                power = next;
+                powers += 1;
+            }
+            f64::from(powers) * (p as f64).ln()
+        })
+        .sum()
+}
+
+/// First Chebyshev function: the sum of `ln p` over primes `p <= x`
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn chebyshev_theta(x: u64) -> f64 {
+    primes_up_to(x).into_iter().map(|p| (p as f64).ln()).sum()
+}
+
+/// Exponent of the prime `p` in `comb(n, k)`, checking the arguments before counting carries with
+/// `kummer_valuation`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `p` is not prime or `k > n`.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn comb_p_adic_valuation(n: u64, k: u64, p: u64) -> MathResult<u64> {
+    if !is_prime_u64(p) {
+        return Err(MathError::Value(
+            "comb_p_adic_valuation() base must be prime",
+        ));
+    }
+    if k > n {
+        return Err(MathError::Value(
+            "comb_p_adic_valuation() undefined for k > n since comb(n, k) is 0",
+        ));
+    }
+    Ok(kummer_valuation(n, k, p))
+}
+
+/// Dirichlet's divisor summatory function `D(n) = sum floor(n / k)` for `1 <= k <= n`
+///
+/// Uses the hyperbola method `D(n) = 2 * sum floor(n / k) for k <= isqrt(n), minus isqrt(n)^2`, which
+/// only needs `O(sqrt n)` divisions
+///
+/// # Errors
+///
+/// Returns [`MathError::Overflow`] if `D(n)` does not fit in a u64.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn divisor_summatory(n: u64) -> MathResult<u64> {
+    let root = n.isqrt();
+    let half: u128 = (1..=root).map(|k| u128::from(n / k)).sum();
+    let total = 2 * half - u128::from(root) * u128::from(root);
+    u64::try_from(total)
+        .map_err(|_| MathError::Overflow("divisor_summatory() result does not fit in 64 bits"))
+}
+
+/// Farey fractions of order `n` in ascending order, each term derived from the previous two
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn farey_sequence(n: u64) -> MathResult<Vec<(u64, u64)>> {
+    if n == 0 {
+        return Err(MathError::Value("farey_sequence() order must be positive"));
+    }
+    let mut sequence = vec![(0, 1)];
+    let (mut previous, mut current) = ((0, 1), (1, n));
+    loop {
+        sequence.push(current);
+        if current == (1, 1) {
+            return Ok(sequence);
+        }
+        let scale = (n + previous.1) / current.1;
+        let next = (
+            scale * current.0 - previous.0,
+            scale * current.1 - previous.1,
+        );
+        (previous, current) = (current, next);
+    }
+}
+
+/// Closest fraction to `x` with denominator at most `max_denominator`, as `Fraction.limit_denominator` finds it
+///
+/// Walks the continued fraction convergents of the exact value of `x` until the next denominator would be
+/// too large, then picks between the last convergent and the best semiconvergent
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `x` is not finite or `max_denominator` is 0, and [`MathError::Overflow`] if the numerator does not fit in an i64.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn rational_approximation(x: f64, max_denominator: u64) -> MathResult<(i64, u64)> {
+    if !x.is_finite() {
+        return Err(MathError::Value(
+            "rational_approximation() argument must be finite",
+        ));
+    }
+    if max_denominator < 1 {
+        return Err(MathError::Value(
+            "rational_approximation() max_denominator should be at least 1",
+        ));
+    }
+    let (numerator, denominator) = float_as_ratio(x);
+    let limit = BigInt::from(max_denominator);
+    let (num, den) = if denominator <= limit {
+        (numerator, denominator)
+    } else {
+        let (mut p0, mut q0, mut p1, mut q1) =
+            (BigInt::ZERO, BigInt::one(), BigInt::one(), BigInt::ZERO);
+        let (mut n, mut d) = (numerator, denominator.clone());
+        loop {
+            let a = n.div_floor(&d);
+            let q2 = &q0 + &a * &q1;
+            if q2 > limit {
+                break;
+            }
+            let p2 = &p0 + &a * &p1;
+            (p0, q0, p1, q1) = (p1, q1, p2, q2);
+            let remainder = &n - &a * &d;
+            (n, d) = (d, remainder);
+        }
+        let k = (&limit - &q0) / &q1;
+        let semiconvergent_den = &q0 + &k * &q1;
+        if BigInt::from(2) * &d * &semiconvergent_den <= denominator {
+            (p1, q1)
+        } else {
+            (p0 + k * p1, semiconvergent_den)
+        }
+    };
+    match (num.to_i64(), den.abs().to_u64()) {
+        (Some(num), Some(den)) => Ok((num, den)),
+        _ => Err(MathError::Overflow(
+            "rational_approximation() numerator does not fit in 64 bits",
+        )),
+    }
+}
+
+/// Row `n` of Pascal's triangle modulo the prime `p`
+///
+/// Rows shorter than `p` come straight from `C(n, k) = C(n, k - 1) * (n - k + 1) / k` with Fermat inverses.
+/// Longer rows would need to invert multiples of `p`, so they are assembled with Lucas's theorem from the
+/// short rows of each base-`p` digit of `n`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `p` is not prime.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "a digit below the u64 modulus always fits in usize"
+)]
+pub fn pascal_row_mod(n: u64, p: u64) -> MathResult<Vec<u64>> {
+    if !is_prime_u64(p) {
+        return Err(MathError::Value("pascal_row_mod() modulus must be prime"));
+    }
+    let short_row = |n: u64| {
+        let mut row = vec![1 % p];
+        for k in 1..=n {
+            let inverse = pow_mod(k, p - 2, p);
+            let next = mul_mod(mul_mod(row[row.len() - 1], n - k + 1, p), inverse, p);
+            row.push(next);
+        }
+        row
+    };
+    let mut digit_rows = Vec::new();
+    let mut remaining = n;
+    loop {
+        digit_rows.push(short_row(remaining % p));
+        remaining /= p;
+        if remaining == 0 {
+            break;
+        }
+    }
+    Ok((0..=n)
+        .map(|k| {
+            let mut k_digits = k;
+            digit_rows.iter().fold(1 % p, |product, row| {
+                let digit = usize::try_from(k_digits % p).expect("digit is below p");
+                k_digits /= p;
+                row.get(digit)
+                    .map_or(0, |&entry| mul_mod(product, entry, p))
+            })
+        })
+        .collect())
+}
+
+/// Number of primes `p` in `[min, max]` with `p ≡ a (mod m)`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `m` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn count_primes_congruent(min: u64, max: u64, a: u64, m: u64) -> MathResult<usize> {
+    if m == 0 {
+        return Err(MathError::Value(
+            "count_primes_congruent() modulus must be positive",
+        ));
+    }
+    let mut count = 0;
+    iterate_primes_congruent(min, max, a, m, |_| count += 1);
+    Ok(count)
+}
+
+/// Decimal digits of `n!`, most significant first, in chunks of `chunk_digits` digits.
+/// Only the leading chunk may be shorter; every later chunk is zero-padded to full width
+///
+/// Chunks are produced lazily: the value is split at `radix^(2^j)` into an upper and lower half, the lower
+/// half is set aside, and the upper one is split again until a single chunk remains. Each chunk therefore
+/// costs only the splits above it, and pieces not yet reached are the only digits held
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is negative or `chunk_digits` is outside `1..=19`.
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "each chunk is below 10^19 and fits in u64"
+)]
+pub fn factorial_digit_chunks(
+    n: i64,
+    chunk_digits: u32,
+) -> MathResult<impl Iterator<Item = String>> {
+    if !(1..=19).contains(&chunk_digits) {
+        return Err(MathError::Value(
+            "factorial_digit_chunks() chunk_digits must be between 1 and 19",
+        ));
+    }
+    let value = factorial(n)?;
+    // powers[j] = radix^(2^j), up to the first one above the value
+    let mut powers = vec![BigUint::from(10_u64.pow(chunk_digits))];
+    while powers[powers.len() - 1] <= value {
+        let last = &powers[powers.len() - 1];
+        powers.push(last * last);
+    }
+    let width = chunk_digits as usize;
+    // Each piece holds exactly 2^level chunks, counting leading zeros
+    let mut pieces = vec![(value, powers.len() - 1)];
+    let mut leading = true;
+    Ok(iter::from_fn(move || {
+        loop {
+            let (piece, level) = pieces.pop()?;
+            if level == 0 {
+                let chunk = piece.to_u64().expect("chunk is below 10^19");
+                let text = if leading {
+                    chunk.to_string()
+                } else {
+                    format!("{chunk:0width$}")
+                };
+                leading = false;
+                return Some(text);
+            }
+            let (upper, lower) = piece.div_rem(&powers[level - 1]);
+            pieces.push((lower, level - 1));
+            // Nothing is emitted before the leading chunk, so an all-zero upper half there is dropped
+            if !(leading && upper.is_zero()) {
+                pieces.push((upper, level - 1));
+            }
+        }
+    }))
+}
+
+/// Decimal digits of `n!` split into `chunk_digits`-digit strings, most significant first
+///
+/// # Errors
+///
+/// Returns `ValueError` if `n` is negative or `chunk_digits` is outside `1..=19`.
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "factorial_digit_chunks")]
+pub fn factorial_digit_chunks_py(n: i64, chunk_digits: u32) -> PyResult<Vec<String>> {
+    Ok(factorial_digit_chunks(n, chunk_digits)?.collect())
+}
+
+/// How many times the decimal digits of `n` must be multiplied together to reach a single digit
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn multiplicative_persistence(n: u64) -> u32 {
+    let mut value = n;
+    let mut steps = 0;
+    while value >= 10 {
+        let mut product = 1;
+        while value > 0 {
+            product *= value % 10;
+            value /= 10;
+        }
+        value = product;
+        steps += 1;
+    }
+    steps
+}
+
+/// Drop the primes that `primes_up_to` keeps between calls
+///
+/// # Panics
+///
+/// Panics if another thread panicked while holding the prime cache lock.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn reset_prime_cache() {
+    *PRIME_CACHE.lock().expect("prime cache lock poisoned") = PrimeCache::new();
+}
+
+/// Kempner's function: the smallest `m` such that `n` divides `m!`
+///
+/// For each `p^e` in the factorization of `n`, the answer is the first multiple of `p` whose factorial has
+/// `p`-adic valuation at least `e`; `n` divides `m!` once every prime power does
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "p * e always satisfies the valuation search"
+)]
+pub fn smarandache(n: u64) -> MathResult<u64> {
+    if n == 0 {
+        return Err(MathError::Value("smarandache() not defined for 0"));
+    }
+    Ok(prime_factorization(n)
+        .into_iter()
+        .map(|(p, exponent)| {
+            (1..=u64::from(exponent))
+                .map(|multiple| multiple * p)
+                .find(|&m| legendre_valuation(m, p) >= u64::from(exponent))
+                .expect("p * exponent always suffices")
+        })
+        .max()
+        .unwrap_or(1))
+}
+
+/// `sigma(n) / n` as a reduced fraction; perfect numbers give exactly 2
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn abundancy_index(n: u64) -> MathResult<(BigUint, BigUint)> {
+    if n == 0 {
+        return Err(MathError::Value("abundancy_index() not defined for 0"));
+    }
+    let sigma = BigUint::from(sum_divisors(n));
+    let n = BigUint::from(n);
+    let divisor = sigma.gcd(&n);
+    Ok((sigma / &divisor, n / divisor))
+}
+
+/// The partner `m != n` of an amicable pair, where each number's proper divisors sum to the other
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn amicable_pair(n: u64) -> Option<u64> {
+    let sum_proper_divisors = |n: u64| sum_divisors(n) - u128::from(n);
+    if n == 0 {
+        return None;
+    }
+    let m = u64::try_from(sum_proper_divisors(n)).ok()?;
+    (m != n && m != 0 && sum_proper_divisors(m) == u128::from(n)).then_some(m)
+}
+
+/// Primality of any non-negative integer: values that fit in a u64 take the deterministic test, and
+/// larger ones go through Miller-Rabin on the `BigUint` directly rather than being truncated
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::needless_pass_by_value)]
+#[must_use]
+pub fn is_prime(n: BigUint) -> bool {
+    match n.to_u64() {
+        Some(small) => is_prime_u64(small),
+        None => is_probable_prime(&n),
+    }
+}
+
+/// Number of primes in `(n, 2n]`, which Bertrand's postulate guarantees is at least 1 for `n >= 1`
+///
+/// # Errors
+///
+/// Returns [`MathError::Overflow`] if `2n` does not fit in a u64.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn count_primes_in_bertrand_interval(n: u64) -> MathResult<usize> {
+    let upper = n.checked_mul(2).ok_or(MathError::Overflow(
+        "count_primes_in_bertrand_interval() 2n does not fit in 64 bits",
+    ))?;
+    Ok(prime_segments(n + 1, upper)
+        .map(|segment| segment.len())
+        .sum())
+}
+
+/// Exponents of the prime factorization of `n`, largest first
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn prime_signature(n: u64) -> Vec<u32> {
+    let mut exponents: Vec<u32> = prime_factorization(n)
+        .into_iter()
+        .map(|(_, exponent)| exponent)
+        .collect();
+    exponents.sort_unstable_by(|a, b| b.cmp(a));
+    exponents
+}
+
+/// Jordan's totient `J_k(n) = n^k * prod(1 - p^-k)` over the distinct primes `p | n`, computed as
+/// `prod p^(k(e - 1)) * (p^k - 1)` so every step stays in the integers
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn jordan_totient(n: u64, k: u32) -> MathResult<BigUint> {
+    if n == 0 {
+        return Err(MathError::Value("jordan_totient() not defined for 0"));
+    }
+    Ok(prime_factorization(n)
+        .into_iter()
+        .map(|(p, exponent)| {
+            let p_to_k = BigUint::from(p).pow(k);
+            p_to_k.pow(exponent - 1) * (p_to_k - 1_u8)
+        })
+        .product())
+}
+
+/// `comb(ns[i], ks[i])` for each pair, sharing the factorial and comb caches across the batch
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `ns` and `ks` differ in length and [`MathError::Overflow`] if a value does not fit in an i64.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn comb_batch(ns: Vec<u64>, ks: Vec<u64>) -> MathResult<Vec<BigUint>> {
+    if ns.len() != ks.len() {
+        return Err(MathError::Value(
+            "comb_batch() ns and ks must have the same length",
+        ));
+    }
+    let to_i64 = |value: u64| {
+        i64::try_from(value)
+            .map_err(|_| MathError::Overflow("comb_batch() argument does not fit in 64 bits"))
+    };
+    ns.iter()
+        .zip(&ks)
+        .map(|(&n, &k)| comb(to_i64(n)?, to_i64(k)?))
+        .collect()
+}
+
+/// `sum phi(k)` for `1 <= k <= n`, reading every totient off a single `LinearSieve` pass
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn totient_summatory(n: u64) -> BigUint {
+    let sieve = LinearSieve::new(n);
+    BigUint::from(
+        sieve
+            .totients()
+            .iter()
+            .map(|&phi| u128::from(phi))
+            .sum::<u128>(),
+    )
+}
+
+/// The Möbius function: 0 if `n` has a squared prime factor, otherwise `(-1)^k` for `k` distinct primes
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn mobius(n: u64) -> MathResult<i8> {
+    if n == 0 {
+        return Err(MathError::Value("mobius() not defined for 0"));
+    }
+    let factors = prime_factorization(n);
+    Ok(if factors.iter().any(|&(_, exponent)| exponent > 1) {
+        0
+    } else if factors.len().is_multiple_of(2) {
+        1
+    } else {
+        -1
+    })
+}
+
+/// The Mertens function `M(n) = sum mobius(k)` for `1 <= k <= n`, reading every `mobius(k)` off a `LinearSieve`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn mertens(n: u64) -> i64 {
+    let sieve = LinearSieve::new(n);
+    sieve.mobius().iter().map(|&mu| i64::from(mu)).sum()
+}
+
+/// Number of distinct values taken by `phi(k)` for `1 <= k <= n`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn totient_image_count(n: u64) -> usize {
+    let sieve = LinearSieve::new(n);
+    let mut values = sieve.totients()[1..].to_vec();
+    values.sort_unstable();
+    values.dedup();
+    values.len()
+}
+
+/// Length of the repeating block in the decimal expansion of `1/n`: the multiplicative order of 10
+/// modulo the part of `n` coprime to 10, or `None` when the expansion terminates
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn repunit_period(n: u64) -> Option<u64> {
+    if n == 0 {
+        return None;
+    }
+    let mut coprime_part = n;
+    for p in [2, 5] {
+        while coprime_part.is_multiple_of(p) {
+            coprime_part /= p;
+        }
+    }
+    multiplicative_order(10, coprime_part)
+}
+
+/// Convergents `h_i / k_i` of the continued fraction `[a0; a1, a2, ...]`, from the recurrences
+/// `h_i = a_i * h_(i-1) + h_(i-2)` and `k_i = a_i * k_(i-1) + k_(i-2)`
+#[must_use]
+pub fn convergents(cf: &[u64]) -> Vec<(BigUint, BigUint)> {
+    let (mut h_prev, mut h) = (BigUint::ZERO, BigUint::one());
+    let (mut k_prev, mut k) = (BigUint::one(), BigUint::ZERO);
+    cf.iter()
+        .map(|&term| {
+            let h_next = term * &h + &h_prev;
+            let k_next = term * &k + &k_prev;
+            h_prev = std::mem::replace(&mut h, h_next);
+            k_prev = std::mem::replace(&mut k, k_next);
+            (h.clone(), k.clone())
+        })
+        .collect()
+}
+
+/// The first `k` convergents of `e`, whose continued fraction is `[2; 1, 2, 1, 1, 4, 1, 1, 6, ...]`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn convergents_of_e(k: usize) -> Vec<(BigUint, BigUint)> {
+    let terms: Vec<u64> = (0..k as u64)
+        .map(|i| match i {
+            0 => 2,
+            i if i % 3 == 2 => 2 * (i + 1) / 3,
+            _ => 1,
+        })
+        .collect();
+    convergents(&terms)
+}
+
+/// The prime `p` with `n = p# ± 1` when `n` is a primorial prime, where `p#` is the product of primes `<= p`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn is_primorial_prime(n: u64) -> Option<u64> {
+    if !is_prime_u64(n) {
+        return None;
+    }
+    let mut primorial = 1_u64;
+    for &p in &SMALL_PRIMES {
+        primorial = primorial.checked_mul(p)?;
+        if primorial - 1 == n || primorial.checked_add(1) == Some(n) {
+            return Some(p);
+        }
+        if primorial - 1 > n {
+            return None;
+        }
+    }
+    None
+}
+
+/// The `k` with `n = k! ± 1` when `n` is a factorial prime
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn is_factorial_prime(n: u64) -> Option<u64> {
+    if !is_prime_u64(n) {
+        return None;
+    }
+    let mut factorial = 1_u64;
+    for k in 1.. {
+        factorial = factorial.checked_mul(k)?;
+        if factorial - 1 == n || factorial.checked_add(1) == Some(n) {
+            return Some(k);
+        }
+        if factorial - 1 > n {
+            return None;
+        }
+    }
+    None
+}
+
+/// `(num / den)^exp` as a reduced fraction, inverting the base first when `exp` is negative
+///
+/// The base is reduced before raising it, and powers of coprime integers stay coprime, so no gcd is
+/// needed on the (much larger) result
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `den` is 0 or zero is raised to a negative power, and [`MathError::Overflow`] if `exp` does not fit in a u32.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "the denominator is made positive before conversion"
+)]
+pub fn rational_pow(num: i64, den: u64, exp: i64) -> MathResult<(BigInt, BigUint)> {
+    if den == 0 {
+        return Err(MathError::Value(
+            "rational_pow() denominator must be nonzero",
+        ));
+    }
+    if num == 0 && exp < 0 {
+        return Err(MathError::Value(
+            "rational_pow() zero cannot be raised to a negative power",
+        ));
+    }
+    let power = u32::try_from(exp.unsigned_abs())
+        .map_err(|_| MathError::Overflow("rational_pow() exponent is too large"))?;
+    let divisor = num.unsigned_abs().gcd(&den).max(1);
+    let (mut numerator, mut denominator) =
+        (BigInt::from(num) / divisor, BigInt::from(den / divisor));
+    if exp < 0 {
+        std::mem::swap(&mut numerator, &mut denominator);
+        if denominator.is_negative() {
+            numerator = -numerator;
+            denominator = -denominator;
+        }
+    }
+    let denominator = denominator
+        .pow(power)
+        .to_biguint()
+        .expect("denominator is positive");
+    Ok((numerator.pow(power), denominator))
+}
+
+/// Largest `n` accepted for `ackermann(3, n) = 2^(n + 3) - 3`, keeping the result to a few megabytes
+const ACKERMANN_MAX_BITS: u64 = 1 << 24;
+
+/// The Ackermann-Péter function, memoized in the math cache
+///
+/// Rows `m <= 3` use their closed forms; rows 4 and 5 recurse through `A(m, n) = A(m - 1, A(m, n - 1))`.
+/// Every other input has a result far too large to store, so it is rejected up front
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] for `(m, n)` whose result is too large to compute.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "feasible inputs keep the inner value within u64"
+)]
+pub fn ackermann(m: u64, n: u64) -> MathResult<BigUint> {
+    let feasible = match m {
+        0..=2 => true,
+        3 => n <= ACKERMANN_MAX_BITS,
+        4 => n <= 2,
+        5 => n == 0,
+        _ => false,
+    };
+    if !feasible {
+        return Err(MathError::Value(
+            "ackermann() result is too large to compute",
+        ));
+    }
+    cached(&ACKERMANN_CACHE, (m, n), || {
+        Ok(match m {
+            0 => BigUint::from(n) + 1_u8,
+            1 => BigUint::from(n) + 2_u8,
+            2 => BigUint::from(n) * 2_u8 + 3_u8,
+            3 => (BigUint::one() << (n + 3)) - 3_u8,
+            _ => {
+                let inner = if n == 0 {
+                    1
+                } else {
+                    ackermann(m, n - 1)?
+                        .to_u64()
+                        .expect("inner value of a feasible input fits in u64")
+                };
+                ackermann(m - 1, inner)?
+            }
+        })
+    })
+}
+
+/// Digits of `n` in `base`, least significant first, peeled off by repeated divmod
+fn digits_le(n: &BigUint, base: u32) -> Vec<u32> {
+    let mut digits = Vec::new();
+    let mut remaining = n.clone();
+    while !remaining.is_zero() {
+        let (quotient, digit) = remaining.div_rem(&BigUint::from(base));
+        digits.push(digit.to_u32().expect("digit is below base"));
+        remaining = quotient;
+    }
+    digits
+}
+
+/// Whether `n` reads the same forwards and backwards in `base`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `base` is below 2.
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (n, base=10)))]
+#[allow(clippy::needless_pass_by_value)]
+pub fn is_palindrome(n: BigUint, base: u32) -> MathResult<bool> {
+    if base < 2 {
+        return Err(MathError::Value("is_palindrome() base must be at least 2"));
+    }
+    let digits = digits_le(&n, base);
+    Ok(digits.iter().eq(digits.iter().rev()))
+}
+
+/// `n` with its digits in `base` reversed, dropping what were trailing zeros
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `base` is below 2.
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (n, base=10)))]
+#[allow(clippy::needless_pass_by_value)]
+pub fn reverse_digits(n: BigUint, base: u32) -> MathResult<BigUint> {
+    if base < 2 {
+        return Err(MathError::Value("reverse_digits() base must be at least 2"));
+    }
+    Ok(digits_le(&n, base)
+        .into_iter()
+        .fold(BigUint::ZERO, |reversed, digit| reversed * base + digit))
+}
+
+/// Number of `3n + 1` / `n / 2` steps needed to bring `n` down to 1
+///
+/// Trajectories can climb well past their starting value, so they are followed in a u128
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn collatz_length(n: u64) -> MathResult<u64> {
+    if n == 0 {
+        return Err(MathError::Value("collatz_length() not defined for 0"));
+    }
+    let mut value = u128::from(n);
+    let mut steps = 0;
+    while value != 1 {
+        value = if value.is_multiple_of(2) {
+            value / 2
+        } else {
+            3 * value + 1
+        };
+        steps += 1;
+    }
+    Ok(steps)
+}
+
+/// Number of unordered prime pairs `p <= q` with `p + q = even_n`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `even_n` is odd.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn goldbach_count(even_n: u64) -> MathResult<usize> {
+    if !even_n.is_multiple_of(2) {
+        return Err(MathError::Value("goldbach_count() argument must be even"));
+    }
+    let primes = primes_up_to(even_n);
+    Ok(primes
+        .iter()
+        .take_while(|&&p| p <= even_n / 2)
+        .filter(|&&p| primes.binary_search(&(even_n - p)).is_ok())
+        .count())
+}
+
+/// Number of prime triplets `(p, p + a, p + b)` with all three members in `[min, max]`, where the offsets
+/// `pattern` are one of the two admissible shapes `[0, 2, 6]` or `[0, 4, 6]`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `pattern` is neither `[0, 2, 6]` nor `[0, 4, 6]`.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn count_prime_triplets(min: u64, max: u64, pattern: [u64; 3]) -> MathResult<usize> {
+    if pattern != [0, 2, 6] && pattern != [0, 4, 6] {
+        return Err(MathError::Value(
+            "count_prime_triplets() pattern must be [0, 2, 6] or [0, 4, 6]",
+        ));
+    }
+    let primes = primes_in_range(min, max);
+    Ok(primes
+        .iter()
+        .filter(|&&p| {
+            pattern[1..].iter().all(|&offset| {
+                p.checked_add(offset)
+                    .is_some_and(|member| primes.binary_search(&member).is_ok())
+            })
+        })
+        .count())
+}
+
+const DIGIT_FACTORIALS: [u64; 10] = [1, 1, 2, 6, 24, 120, 720, 5_040, 40_320, 362_880];
+
+/// Whether `n` equals the sum of the factorials of its decimal digits
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn is_factorion(n: u64) -> bool {
+    let mut remaining = n;
+    let mut sum = 0;
+    loop {
+        sum += DIGIT_FACTORIALS[(remaining % 10) as usize];
+        remaining /= 10;
+        if remaining == 0 {
+            break;
+        }
+    }
+    sum == n
+}
+
+/// A random number of about `bits` bits whose prime factors are all at most `smoothness`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `smoothness` is below 2.
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (bits, smoothness, seed=None)))]
+pub fn random_smooth_number(bits: u32, smoothness: u64, seed: Option<u64>) -> MathResult<BigUint> {
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    };
+    random_smooth_number_with_rng(bits, smoothness, &mut rng)
+}
+
+/// Largest prime factor of any `n >= 2`, splitting composites with Pollard's rho rather than trial
+/// division so that products of two large primes stay fast
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is below 2.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "n >= 2 always has a prime factor"
+)]
+pub fn largest_prime_factor_big(n: u64) -> MathResult<u64> {
+    if n < 2 {
+        return Err(MathError::Value(
+            "largest_prime_factor_big() not defined for values below 2",
+        ));
+    }
+    let mut factors = Vec::new();
+    rho_prime_factors(n, &mut factors);
+    Ok(factors
+        .into_iter()
+        .max()
+        .expect("n >= 2 has a prime factor"))
+}
+
+/// Prime factorization of any u64 as ascending `(prime, exponent)` pairs
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn factorize_u64(n: u64) -> Vec<(u64, u32)> {
+    prime_factorization(n)
+}
+
+/// Sum of the proper divisors of `n`, which equals `n` exactly when `n` is perfect
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn aliquot_sum(n: u64) -> MathResult<BigUint> {
+    if n == 0 {
+        return Err(MathError::Value("aliquot_sum() not defined for 0"));
+    }
+    Ok(BigUint::from(sum_divisors(n) - u128::from(n)))
+}
+
+/// `n` followed by repeated aliquot sums, ending at 0, after `max_steps` steps, or once a term would not
+/// fit in a u64
+pub fn aliquot_sequence(n: u64, max_steps: usize) -> impl Iterator<Item = u64> {
+    std::iter::successors(Some(n), |&term| {
+        if term == 0 {
+            return None;
+        }
+        u64::try_from(sum_divisors(term) - u128::from(term)).ok()
+    })
+    .take(max_steps.saturating_add(1))
+}
+
+/// `n` followed by at most `max_steps` repeated aliquot sums, stopping early at 0
+#[cfg(feature = "python")]
+#[pyfunction]
+#[pyo3(name = "aliquot_sequence")]
+#[must_use]
+pub fn aliquot_sequence_py(n: u64, max_steps: usize) -> Vec<u64> {
+    aliquot_sequence(n, max_steps).collect()
+}
+
+/// Whether every positive integer below `n` is a sum of distinct divisors of `n`
+///
+/// Uses Stewart's criterion: with primes `p_1 < p_2 < ...` dividing `n`, the smallest must be 2 and each
+/// `p_(i+1)` must be at most `1 + sigma(p_1^e_1 ... p_i^e_i)`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn is_practical(n: u64) -> bool {
+    if n == 0 {
+        return false;
+    }
+    let mut prefix_sigma = 1_u128;
+    for (p, exponent) in prime_factorization(n) {
+        if u128::from(p) > prefix_sigma + 1 {
+            return false;
+        }
+        let p = u128::from(p);
+        prefix_sigma *= (p.pow(exponent + 1) - 1) / (p - 1);
+    }
+    true
+}
+
+/// `n!` in one of the representations accepted by `factorial_as`
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "python", derive(IntoPyObject))]
+pub enum FactorialRepr {
+    Int(BigUint),
+    Hex(String),
+    Bytes(Vec<u8>),
+}
+
+/// `n!` as an "int", a lowercase "hex" string without prefix, or big-endian "bytes"
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is negative or `fmt` is not `"int"`, `"hex"` or `"bytes"`.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn factorial_as(n: i64, fmt: &str) -> MathResult<FactorialRepr> {
+    let value = factorial(n)?;
+    match fmt {
+        "int" => Ok(FactorialRepr::Int(value)),
+        "hex" => Ok(FactorialRepr::Hex(value.to_str_radix(16))),
+        "bytes" => Ok(FactorialRepr::Bytes(value.to_bytes_be())),
+        _ => Err(MathError::Value(
+            "factorial_as() fmt must be \"int\", \"hex\" or \"bytes\"",
+        )),
+    }
+}
+
+/// `isqrt` for a stream of non-decreasing inputs, stepping the previous root up instead of starting over
+///
+/// Consecutive inputs usually share a root or move it by one, so a couple of u128 squarings replace the full
+/// Newton iteration `isqrt` runs on every call
+#[derive(Debug, Default)]
+pub struct IncrementalIsqrt {
+    root: u64,
+}
+
+impl IncrementalIsqrt {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `floor(sqrt(n))`, found by stepping the previous root up by at most one; an `n` below the previous
+    /// input, or far enough above it to need a larger step, falls back to a fresh computation
+    pub fn isqrt(&mut self, n: u64) -> u64 {
+        let square = |root: u64| u128::from(root) * u128::from(root);
+        let n_wide = u128::from(n);
+        if square(self.root) > n_wide || square(self.root + 2) <= n_wide {
+            self.root = n.isqrt();
+        } else if square(self.root + 1) <= n_wide {
+            self.root += 1;
+        }
+        self.root
+    }
+}
+
+/// Number of primes in `[low, high]` whose binary representation has exactly `weight` set bits
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn count_primes_with_binary_weight(low: u64, high: u64, weight: u32) -> usize {
+    prime_segments(low, high)
+        .flatten()
+        .filter(|p| p.count_ones() == weight)
+        .count()
+}
+
+/// The `k`-th prime, 1-based, sieving only the window between Dusart's bounds
+/// `k (ln k + ln ln k - 1) <= p_k <= k (ln k + ln ln k)` after counting the primes below it segment by
+/// segment, without keeping them
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `k` is 0 and [`MathError::Overflow`] if `p_k` does not fit in a u64.
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "k is below the table size on that path"
+)]
+pub fn nth_prime_fast(k: u64) -> MathResult<u64> {
+    if k == 0 {
+        return Err(MathError::Value("nth_prime_fast() index must be positive"));
+    }
+    if k <= SMALL_PRIME_COUNT as u64 {
+        return Ok(SMALL_PRIMES[usize::try_from(k - 1).expect("index is below the table size")]);
+    }
+    let estimate = k as f64 * ((k as f64).ln() + (k as f64).ln().ln());
+    nth_prime_between(
+        k,
+        (estimate - k as f64).floor() as u64,
+        estimate.ceil() as u64,
+    )
+}
+
+/// The `k`-th prime, expected in `(lower, upper]`; rounding in the float bounds can leave it just outside,
+/// so the window is widened and sieved again until it holds `p_k`
+fn nth_prime_between(k: u64, mut lower: u64, mut upper: u64) -> MathResult<u64> {
+    loop {
+        let below = count_primes_up_to(lower);
+        if below >= k {
+            lower /= 2;
+            continue;
+        }
+        let window = primes_in_range(lower + 1, upper);
+        let offset = usize::try_from(k - below - 1).expect("offset fits in usize");
+        if let Some(&p) = window.get(offset) {
+            return Ok(p);
+        }
+        upper = upper
+            .checked_add((upper - lower).max(1))
+            .ok_or(MathError::Overflow(
+                "nth_prime_fast() result does not fit in 64 bits",
+            ))?;
+    }
+}
+
+/// For each `k` in `[start, start + count)`, the value of the polynomial with coefficients `coeffs`
+/// (constant term first) at `k` when that value is prime, and `None` otherwise, including when it is
+/// negative or does not fit in a u64
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::needless_pass_by_value)]
+#[must_use]
+pub fn polynomial_prime_values(coeffs: Vec<i64>, start: u64, count: u64) -> Vec<Option<u64>> {
+    let evaluate = |k: u64| {
+        coeffs.iter().rev().try_fold(0_i128, |value, &coefficient| {
+            value
+                .checked_mul(i128::from(k))?
+                .checked_add(i128::from(coefficient))
+        })
+    };
+    (start..start.saturating_add(count))
+        .map(|k| {
+            evaluate(k)
+                .and_then(|value| u64::try_from(value).ok())
+                .filter(|&value| is_prime_u64(value))
+        })
+        .collect()
+}
+
+/// The radical of `n`: the product of its distinct prime factors
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn radical(n: u64) -> MathResult<u64> {
+    if n == 0 {
+        return Err(MathError::Value("radical() not defined for 0"));
+    }
+    Ok(prime_factorization(n).into_iter().map(|(p, _)| p).product())
+}
+
+/// `radical(n)` for each `n` in `[low, high]`, peeling distinct primes off with a `LinearSieve`'s smallest
+/// prime factors instead of factoring each `n` separately
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `low` is 0.
+///
+/// # Panics
+///
+/// Panics if `high` does not fit in a `usize`.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn radical_range(low: u64, high: u64) -> MathResult<Vec<u64>> {
+    if low == 0 {
+        return Err(MathError::Value("radical_range() not defined for 0"));
+    }
+    let sieve = LinearSieve::new(high);
+    let smallest_prime_factors = sieve.smallest_prime_factors();
+    Ok((low..=high)
+        .map(|n| {
+            let mut remaining = n;
+            let mut radical = 1;
+            while remaining > 1 {
+                let p = smallest_prime_factors
+                    [usize::try_from(remaining).expect("n is within the sieve")];
+                radical *= p;
+                while remaining.is_multiple_of(p) {
+                    remaining /= p;
+                }
+            }
+            radical
+        })
+        .collect())
+}
+
+/// Number of ordered ways to write `n` as a sum of primes, where `ways(m) = sum ways(m - p)` over primes
+/// `p <= m`
+///
+/// # Panics
+///
+/// Panics if `n` does not fit in a `usize`.
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn prime_compositions(n: u64) -> BigUint {
+    let limit = usize::try_from(n).expect("prime_compositions() argument must fit in usize");
+    let primes: Vec<usize> = primes_up_to(n)
+        .into_iter()
+        .map(|p| usize::try_from(p).expect("p is at most n"))
+        .collect();
+    let mut ways = vec![BigUint::ZERO; limit + 1];
+    ways[0] = BigUint::one();
+    for m in 1..=limit {
+        let total = primes
+            .iter()
+            .take_while(|&&p| p <= m)
+            .map(|&p| &ways[m - p])
+            .sum();
+        ways[m] = total;
+    }
+    ways.swap_remove(limit)
+}
+
+/// Number of ways to write `n` as an unordered sum of primes, filling the table one prime at a time as
+/// in the coin-change problem so each multiset is counted once
+///
+/// # Panics
+///
+/// Panics if `n` does not fit in a `usize`.
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn prime_partitions(n: u64) -> BigUint {
+    let limit = usize::try_from(n).expect("prime_partitions() argument must fit in usize");
+    let mut ways = vec![BigUint::ZERO; limit + 1];
+    ways[0] = BigUint::one();
+    for p in primes_up_to(n) {
+        let p = usize::try_from(p).expect("p is at most n");
+        for m in p..=limit {
+            let (filled, rest) = ways.split_at_mut(m);
+            rest[0] += &filled[m - p];
+        }
+    }
+    ways.swap_remove(limit)
+}
+
+/// The Sophie Germain primes in `[min, max]`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn sophie_germain_primes(min: u64, max: u64) -> Vec<u64> {
+    let mut primes = Vec::new();
+    iterate_sophie_germain(min, max, |p| primes.push(p));
+    primes
+}
+
+/// Whether `p` is a safe prime: `p` and `(p - 1) / 2` are both prime
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn is_safe_prime(p: u64) -> bool {
+    p > 4 && is_prime_u64(p) && is_prime_u64((p - 1) / 2)
+}
+
+/// First members of the prime quadruplets lying entirely within `[min, max]`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn prime_quadruplets(min: u64, max: u64) -> Vec<u64> {
+    let mut starts = Vec::new();
+    iterate_prime_quadruplets(min, max, |p| starts.push(p));
+    starts
+}
+
+/// Pillai's function `sum gcd(k, n)` for `1 <= k <= n`
+///
+/// `sum_(d | n) d * phi(n / d)` is multiplicative, and on a prime power it collapses to
+/// `(e + 1) p^e - e p^(e - 1)`, so only the factorization of `n` is needed
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn gcd_sum(n: u64) -> BigUint {
+    if n == 0 {
+        return BigUint::ZERO;
+    }
+    prime_factorization(n)
+        .into_iter()
+        .map(|(p, exponent)| {
+            let lower = BigUint::from(p).pow(exponent - 1);
+            &lower * p * (exponent + 1) - lower * exponent
+        })
+        .product()
+}
+
+/// `r2(n) / 4`: the number of pairs `(a, b)` with `a > 0`, `b >= 0` and `a^2 + b^2 = n`
+///
+/// This is the product of `e + 1` over primes `p ≡ 1 (mod 4)`, or 0 when some prime `p ≡ 3 (mod 4)`
+/// appears to an odd power
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn sum_of_two_squares_count(n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    let mut count = 1;
+    for (p, exponent) in prime_factorization(n) {
+        match p % 4 {
+            1 => count *= u64::from(exponent) + 1,
+            3 if exponent % 2 == 1 => return 0,
+            _ => {}
+        }
+    }
+    count
+}
+
+/// Euler's totient: how many `1 <= k <= n` are coprime to `n`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn totient(n: u64) -> MathResult<u64> {
+    if n == 0 {
+        return Err(MathError::Value("totient() not defined for 0"));
+    }
+    Ok(prime_factorization(n)
+        .into_iter()
+        .map(|(p, exponent)| p.pow(exponent - 1) * (p - 1))
+        .product())
+}
+
+/// How many applications of `totient` take `n` down to 1
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn totient_chain_length(n: u64) -> MathResult<u32> {
+    let mut value = n;
+    let mut steps = 0;
+    while value != 1 {
+        value = totient(value)
+            .map_err(|_| MathError::Value("totient_chain_length() not defined for 0"))?;
+        steps += 1;
+    }
+    Ok(steps)
+}
+
+/// Prime factorization of `comb(n, k)` as ascending `(prime, exponent)` pairs, without building the
+/// binomial itself
+///
+/// Each exponent comes from `kummer_valuation`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `k > n`.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn comb_factorization(n: u64, k: u64) -> MathResult<Vec<(u64, u64)>> {
+    if k > n {
+        return Err(MathError::Value(
+            "comb_factorization() k must not exceed n, since comb(n, k) is 0",
+        ));
+    }
+    Ok(primes_up_to(n)
+        .into_iter()
+        .map(|p| (p, kummer_valuation(n, k, p)))
+        .filter(|&(_, exponent)| exponent > 0)
+        .collect())
+}
+
+/// `log10(n!)` from `lgamma(n + 1) / ln(10)`, for sizing `n!` without computing it
+#[cfg_attr(feature = "python", pyfunction)]
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn factorial_log10(n: u64) -> f64 {
+    lgamma(n as f64 + 1.0) / std::f64::consts::LN_10
+}
+
+/// The primes in `[min, max]` whose decimal digits match `pattern` digit for digit, where `_` matches
+/// any digit, so `"1_3"` selects three-digit primes starting with 1 and ending with 3
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `pattern` contains anything but digits and `_`.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn primes_matching(min: u64, max: u64, pattern: &str) -> MathResult<Vec<u64>> {
+    let pattern: Vec<Option<u8>> = pattern
+        .chars()
+        .map(|symbol| match symbol {
+            '_' => Ok(None),
+            '0'..='9' => Ok(Some(symbol as u8 - b'0')),
+            _ => Err(MathError::Value(
+                "primes_matching() pattern may only contain digits and '_'",
+            )),
+        })
+        .collect::<MathResult<_>>()?;
+    let mut primes = Vec::new();
+    iterate_primes_matching(
+        min,
+        max,
+        |digits| {
+            digits.len() == pattern.len()
+                && digits
+                    .iter()
+                    .zip(&pattern)
+                    .all(|(&digit, expected)| expected.is_none_or(|expected| digit == expected))
+        },
+        |p| primes.push(p),
+    );
+    Ok(primes)
+}
+
+/// Product of the integers in `[start, end]` modulo `m`, without building the full product
+///
+/// A range of at least `m` consecutive integers contains a multiple of `m`, so its product is 0 outright
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `m` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn product_range_mod(start: u64, end: u64, m: u64) -> MathResult<u64> {
+    if m == 0 {
+        return Err(MathError::Value(
+            "product_range_mod() modulus must be positive",
+        ));
+    }
+    if start <= end && end - start >= m - 1 {
+        return Ok(0);
+    }
+    Ok((start..=end).fold(1 % m, |product, i| mul_mod(product, i, m)))
+}
+
+/// Number of trailing zeros of `comb(n, k)` written in `base`: the smallest `v_p(comb(n, k)) / e` over the
+/// prime powers `p^e` in `base`, with each valuation from Kummer's theorem
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `base` is below 2 or `k > n`.
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (n, k, base=10)))]
+#[allow(
+    clippy::missing_panics_doc,
+    reason = "base >= 2 always has a prime factor"
+)]
+pub fn comb_trailing_zeros(n: u64, k: u64, base: u64) -> MathResult<u64> {
+    if base < 2 {
+        return Err(MathError::Value(
+            "comb_trailing_zeros() base must be at least 2",
+        ));
+    }
+    if k > n {
+        return Err(MathError::Value(
+            "comb_trailing_zeros() k must not exceed n, since comb(n, k) is 0",
+        ));
+    }
+    Ok(prime_factorization(base)
+        .into_iter()
+        .map(|(p, exponent)| kummer_valuation(n, k, p) / u64::from(exponent))
+        .min()
+        .expect("base >= 2 has a prime factor"))
+}
+
+/// The `n`-th `sides`-gonal number `P(s, n) = ((s - 2) n^2 - (s - 4) n) / 2`, computed as
+/// `n ((s - 2)(n - 1) + 2) / 2` so no intermediate goes negative
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `sides` is below 3.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn figurate(sides: u64, n: u64) -> MathResult<BigUint> {
+    if sides < 3 {
+        return Err(MathError::Value("figurate() needs at least 3 sides"));
+    }
+    if n == 0 {
+        return Ok(BigUint::ZERO);
+    }
+    let n = BigUint::from(n);
+    Ok(&n * ((sides - 2) * (&n - 1_u8) + 2_u8) / 2_u8)
+}
+
+/// Whether `x` is a `sides`-gonal number, by solving `P(s, n) = x` for `n` with the quadratic formula
+/// `n = ((s - 4) + sqrt((s - 4)^2 + 8 (s - 2) x)) / (2 (s - 2))` and checking the root is a whole number
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `sides` is below 3.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn is_figurate(sides: u64, x: u64) -> MathResult<bool> {
+    if sides < 3 {
+        return Err(MathError::Value("is_figurate() needs at least 3 sides"));
+    }
+    // P(s, 0) = 0 is the other root of the quadratic, which the formula below skips when s > 4
+    if x == 0 {
+        return Ok(true);
+    }
+    let offset = BigInt::from(sides) - 4_u8;
+    let discriminant: BigInt = &offset * &offset + BigInt::from(8_u8) * (sides - 2) * x;
+    let root = discriminant.sqrt();
+    if &root * &root != discriminant {
+        return Ok(false);
+    }
+    let (n, remainder) = (offset + root).div_rem(&(BigInt::from(2_u8) * (sides - 2)));
+    Ok(remainder.is_zero() && !n.is_negative())
+}
+
+/// Whether `comb(n, k)` is odd, which by Lucas's theorem mod 2 holds exactly when the bits of `k` are a
+/// subset of the bits of `n`
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn comb_is_odd(n: u64, k: u64) -> bool {
+    k & n == k
+}
+
+/// Sum over the primes in `[low, high]` of their decimal digit sums
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn sum_prime_digit_sums(low: u64, high: u64) -> u64 {
+    let digit_sum = |mut p: u64| {
+        let mut sum = 0;
+        while p > 0 {
+            sum += p % 10;
+            p /= 10;
+        }
+        sum
+    };
+    prime_segments(low, high).flatten().map(digit_sum).sum()
+}
+
+/// `(pre-period, period)` of the expansion of `1/n` in `base`
+///
+/// The pre-period is the largest `ceil(v_p(n) / v_p(base))` over the primes `p` dividing both; the period
+/// is the multiplicative order of `base` modulo what remains of `n` after removing those primes, or 0 when
+/// nothing remains and the expansion terminates
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0 or `base` is below 2.
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (n, base=10)))]
+pub fn fraction_period(n: u64, base: u64) -> MathResult<(u64, u64)> {
+    if n == 0 {
+        return Err(MathError::Value("fraction_period() not defined for 0"));
+    }
+    if base < 2 {
+        return Err(MathError::Value(
+            "fraction_period() base must be at least 2",
+        ));
+    }
+    let mut coprime_part = n;
+    let mut pre_period = 0;
+    for (p, base_exponent) in prime_factorization(base) {
+        let mut exponent = 0;
+        while coprime_part.is_multiple_of(p) {
+            coprime_part /= p;
+            exponent += 1;
+        }
+        pre_period = pre_period.max(u64::div_ceil(exponent, u64::from(base_exponent)));
+    }
+    Ok((
+        pre_period,
+        multiplicative_order(base, coprime_part).unwrap_or(0),
+    ))
+}
+
+/// Dedekind's psi function `n * prod(1 + 1/p)` over the distinct primes `p` dividing `n`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn dedekind_psi(n: u64) -> MathResult<BigUint> {
+    if n == 0 {
+        return Err(MathError::Value("dedekind_psi() not defined for 0"));
+    }
+    Ok(prime_factorization(n)
+        .into_iter()
+        .map(|(p, exponent)| BigUint::from(p).pow(exponent - 1) * (BigUint::from(p) + 1_u8))
+        .product())
+}
+
+fn harshad_check(n: u64, base: u64) -> bool {
+    let mut digit_sum = 0;
+    let mut rest = n;
+    while rest > 0 {
+        digit_sum += rest % base;
+        rest /= base;
+    }
+    n.is_multiple_of(digit_sum)
+}
+
+/// Whether `n` is a Harshad (Niven) number, divisible by the sum of its digits in `base`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `n` is 0 or `base` is below 2.
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (n, base=10)))]
+pub fn is_harshad(n: u64, base: u64) -> MathResult<bool> {
+    if base < 2 {
+        return Err(MathError::Value("is_harshad() base must be at least 2"));
+    }
+    if n == 0 {
+        return Err(MathError::Value("is_harshad() not defined for 0"));
+    }
+    Ok(harshad_check(n, base))
+}
+
+/// The smallest Harshad number in `base` strictly greater than `n`
+///
+/// # Errors
+///
+/// Returns [`MathError::Value`] if `base` is below 2 and [`MathError::Overflow`] if no Harshad number above `n` fits in a u64.
+#[cfg_attr(feature = "python", pyfunction)]
+#[cfg_attr(feature = "python", pyo3(signature = (n, base=10)))]
+pub fn next_harshad(n: u64, base: u64) -> MathResult<u64> {
+    if base < 2 {
+        return Err(MathError::Value("next_harshad() base must be at least 2"));
+    }
+    n.checked_add(1)
+        .and_then(|start| (start..=u64::MAX).find(|&k| harshad_check(k, base)))
+        .ok_or(MathError::Overflow(
+            "next_harshad() result does not fit in 64 bits",
+        ))
+}
+
+/// Integer cube root: the largest `r` with `r^3 <= n`, correcting the float estimate in both directions
+fn icbrt(n: u64) -> u64 {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    let mut root = (n as f64).cbrt() as u64;
+    let cube = |r: u64| u128::from(r).pow(3);
+    while cube(root) > u128::from(n) {
+        root -= 1;
+    }
+    while cube(root + 1) <= u128::from(n) {
+        root += 1;
+    }
+    root
+}
+
+/// Every pair `(a, b)` of positive integers with `a <= b` and `a^3 + b^3 = n`, in increasing `a`
+///
+/// `a^3 <= n / 2` bounds the smaller cube, and the larger is checked with an integer cube root
+#[cfg_attr(feature = "python", pyfunction)]
+#[must_use]
+pub fn ways_as_sum_of_two_cubes(n: u64) -> Vec<(u64, u64)> {
+    (1..=icbrt(n / 2))
+        .filter_map(|a| {
+            let rest = n - a * a * a;
+            let b = icbrt(rest);
+            (b * b * b == rest).then_some((a, b))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    extern crate test;
+    use test::Bencher;
+
+    #[test]
+    fn test_isqrt() {
+        for i in 4..10000 {
+            let square: i64 = i * i;
+            if let Ok(less_result) = isqrt(square - 1) {
+                assert_eq!(
+                    less_result,
+                    i - 1,
+                    "one less of square {} should be {}",
+                    less_result,
+                    i - 1
+                );
+            }
+            assert_eq!(isqrt(square).unwrap(), i);
+            if let Ok(greater_result) = isqrt(square + 1) {
+                assert_eq!(
+                    greater_result, i,
+                    "one greater of square {greater_result} should be {i}",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_comb_matches_factorial_ratio() {
+        for n in 0..=60_i64 {
+            for k in 0..=n {
+                let expected = factorial_biguint(n as u64)
+                    / (factorial_biguint(k as u64) * factorial_biguint((n - k) as u64));
+                assert_eq!(comb(n, k).unwrap(), expected, "comb({n}, {k})");
+            }
+        }
+        assert_eq!(comb(3, 4).unwrap(), BigUint::ZERO);
+        assert_eq!(
+            comb(5, -1),
+            Err(MathError::Value("k must be a non-negative integer"))
+        );
+    }
+
+    #[test]
+    fn test_small_prime_table_matches_runtime_sieve() {
+        assert_eq!(
+            SMALL_PRIMES.to_vec(),
+            sieve_of_eratosthenes(SMALL_PRIME_LIMIT as u64)
+        );
+        for n in (0..=10_010)
+            .step_by(37)
+            .chain([0, 1, 2, 3, 9_999, 10_000, 10_001])
+        {
+            assert_eq!(
+                primes_up_to(n),
+                sieve_of_eratosthenes(n),
+                "primes_up_to({n})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_primorial_power_matches_factorial() {
+        for n in 0..=2000 {
+            assert_eq!(
+                primorial_power(n).unwrap(),
+                factorial_biguint(n as u64),
+                "primorial_power({n}) should equal {n}!"
+            );
+        }
+    }
+
+    #[test]
+    fn test_random_prime_is_reproducible_and_prime() {
+        let primes = primes_up_to(10_000);
+        for seed in 0..100 {
+            let first = random_prime(1_000, 10_000, Some(seed)).unwrap();
+            let second = random_prime(1_000, 10_000, Some(seed)).unwrap();
+            assert_eq!(first, second, "seed {seed} should reproduce the same prime");
+            assert!((1_000..=10_000).contains(&first));
+            assert!(
+                primes.binary_search(&first).is_ok(),
+                "{first} should be prime"
+            );
+        }
+        assert_eq!(random_prime(24, 28, Some(0)).ok(), None);
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_binomial_pmf_matches_direct_computation() {
+        for n in 0..=20 {
+            for k in 0..=n {
+                let comb = (factorial_u64(n) / (factorial_u64(k) * factorial_u64(n - k))) as f64;
+                for p in [0.0, 0.1, 0.25, 0.5, 0.9, 1.0_f64] {
+                    let expected = comb * p.powf(k as f64) * (1.0 - p).powf((n - k) as f64);
+                    let actual = binomial_pmf(n, k, p).unwrap();
+                    assert!(
+                        (actual - expected).abs() <= 1e-12,
+                        "binomial_pmf({n}, {k}, {p}) = {actual}, expected {expected}"
+                    );
+                }
+            }
+        }
+        assert!(binomial_pmf(10, 3, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_binomial_pmf_sums_to_one() {
+        for (n, p) in [(10, 0.5), (1000, 0.3), (5000, 0.01)] {
+            let total: f64 = (0..=n).map(|k| binomial_pmf(n, k, p).unwrap()).sum();
+            assert!((total - 1.0).abs() < 1e-9, "PMF over n={n} sums to {total}");
+        }
+    }
+
+    #[test]
+    fn test_binomial_probability_exact() {
+        assert_eq!(
+            binomial_probability_exact(2, 1, 1, 2).unwrap(),
+            (BigUint::from(1_u32), BigUint::from(2_u32))
+        );
+        assert_eq!(
+            binomial_probability_exact(3, 2, 1, 3).unwrap(),
+            (BigUint::from(2_u32), BigUint::from(9_u32))
+        );
+        assert_eq!(
+            binomial_probability_exact(2, 3, 1, 2).unwrap(),
+            (BigUint::ZERO, BigUint::from(1_u32))
+        );
+        assert!(binomial_probability_exact(2, 1, 3, 2).is_err());
+    }
+
+    #[test]
+    fn test_enumerate_primes() {
+        assert_eq!(
+            enumerate_primes(1, 20),
+            vec![
+                (1, 2),
+                (2, 3),
+                (3, 5),
+                (4, 7),
+                (5, 11),
+                (6, 13),
+                (7, 17),
+                (8, 19)
+            ]
+        );
+        assert_eq!(enumerate_primes(10, 20)[0], (5, 11));
+        assert_eq!(enumerate_primes(24, 28), vec![]);
+    }
+
+    #[test]
+    fn test_primorial_mod() {
+        assert_eq!(primorial_mod(10, 1000).unwrap(), 210);
+        assert_eq!(primorial_mod(1, 7).unwrap(), 1);
+        assert_eq!(primorial_mod(10, 1).unwrap(), 0);
+        for n in [100, 1_000, 20_000] {
+            let primorial: BigUint = primes_up_to(n).into_iter().map(BigUint::from).product();
+            for m in [2, 97, 1_000_000_007, u64::MAX] {
+                assert_eq!(
+                    BigUint::from(primorial_mod(n, m).unwrap()),
+                    &primorial % m,
+                    "primorial({n}) mod {m}"
+                );
+            }
+        }
+        assert!(primorial_mod(10, 0).is_err());
+    }
+
+    #[test]
+    fn test_comb_estimate_relative_error() {
+        for n in (0..=1000_i64).step_by(25) {
+            for k in 0..=n {
+                let exact = comb(n, k).unwrap().to_f64().unwrap();
+                let estimate = comb_estimate(n as u64, k as u64);
+                let relative_error = ((estimate - exact) / exact).abs();
+                assert!(
+                    relative_error < 1e-10,
+                    "comb_estimate({n}, {k}) = {estimate}, exact {exact}"
+                );
+            }
+        }
+        assert!(comb_estimate(3, 4).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_twin_prime_count() {
+        assert_eq!(twin_prime_count(0, 100), 8);
+        assert_eq!(twin_prime_count(4, 100), 7);
+        assert_eq!(twin_prime_count(0, 6), 1);
+        assert_eq!(twin_prime_count(0, 7), 2);
+        assert_eq!(twin_prime_count(0, 1000), 35);
+        assert_eq!(twin_prime_count(24, 28), 0);
+    }
+
+    #[test]
+    fn test_ipow_checked() {
+        assert_eq!(ipow_checked(2, 63).unwrap(), 1 << 63);
+        assert!(ipow_checked(2, 64).is_err());
+        assert_eq!(ipow_checked(10, 19).unwrap(), 10_000_000_000_000_000_000);
+        assert!(ipow_checked(10, 20).is_err());
+        assert_eq!(ipow_checked(0, 0).unwrap(), 1);
+        assert_eq!(ipow_checked(u64::MAX, 1).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn test_first_n_primes() {
+        assert_eq!(first_n_primes(10), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(first_n_primes(0), Vec::<u64>::new());
+        for k in 1..=2000 {
+            let primes = first_n_primes(k);
+            assert_eq!(primes.len() as u64, k);
+            assert_eq!(enumerate_primes(0, *primes.last().unwrap()).len() as u64, k);
+        }
+        assert_eq!(*first_n_primes(100_000).last().unwrap(), 1_299_709);
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        let primes = primes_up_to(30_000);
+        for n in 0..30_000_u64 {
+            assert_eq!(
+                is_probable_prime(&BigUint::from(n)),
+                primes.binary_search(&n).is_ok(),
+                "is_probable_prime({n})"
+            );
+        }
+        let mersenne_127 = (BigUint::one() << 127_u32) - 1_u32;
+        assert!(is_probable_prime(&mersenne_127));
+        assert!(!is_probable_prime(&(&mersenne_127 * &mersenne_127)));
+        assert!(!is_probable_prime(&BigUint::from(3_215_031_751_u64)));
+    }
+
+    #[test]
+    fn test_prime_with_bit_length() {
+        for bits in [2, 3, 8, 17, 32, 64, 128, 256] {
+            for seed in 0..5 {
+                let prime = prime_with_bit_length(bits, Some(seed)).unwrap();
+                assert_eq!(prime.bits(), u64::from(bits));
+                assert!(is_probable_prime(&prime), "{prime} should be prime");
+                assert_eq!(prime, prime_with_bit_length(bits, Some(seed)).unwrap());
+            }
+        }
+        let small = prime_with_bit_length(20, Some(7))
+            .unwrap()
+            .to_u64()
+            .unwrap();
+        assert!(primes_up_to(1 << 20).binary_search(&small).is_ok());
+        assert!(prime_with_bit_length(1, Some(0)).is_err());
+    }
+
+    #[test]
+    fn test_is_prime_trace() {
+        let (is_prime, trace) = is_prime_trace(91);
+        assert!(!is_prime);
+        assert!(trace.contains(&"7 divides 91, so it is composite".to_string()));
+
+        let (is_prime, trace) = is_prime_trace(97);
+        assert!(is_prime);
+        assert_eq!(
+            trace.last().unwrap(),
+            "no prime up to isqrt(97) divides 97, so it is prime"
+        );
+
+        let (is_prime, trace) = is_prime_trace(10_007 * 10_009);
+        assert!(!is_prime);
+        assert!(trace.last().unwrap().contains("witness"));
+
+        assert!(is_prime_trace(1_000_000_007).0);
+        assert!(is_prime_trace(18_446_744_073_709_551_557).0);
+        assert!(!is_prime_trace(3_215_031_751).0);
+        assert!(!is_prime_trace(1).0);
+
+        let primes = primes_up_to(20_000);
+        for n in 0..20_000 {
+            assert_eq!(is_prime_trace(n).0, primes.binary_search(&n).is_ok(), "{n}");
+        }
+    }
+
+    #[test]
+    fn test_factorial_factorization() {
+        assert_eq!(
+            factorial_factorization(10),
+            vec![(2, 8), (3, 4), (5, 2), (7, 1)]
+        );
+        assert_eq!(factorial_factorization(1), vec![]);
+        for n in (0..=1000).step_by(7) {
+            let product: BigUint = factorial_factorization(n)
+                .into_iter()
+                .map(|(p, e)| BigUint::from(p).pow(u32::try_from(e).unwrap()))
+                .product();
+            assert_eq!(product, factorial_biguint(n), "factorization of {n}!");
+        }
+    }
+
+    #[test]
+    fn test_count_primes_by_last_digit() {
+        assert_eq!(primes_by_last_digit(0, 100), [0, 5, 1, 7, 0, 1, 0, 6, 0, 5]);
+        assert_eq!(
+            primes_by_last_digit(10, 100),
+            [0, 5, 0, 6, 0, 0, 0, 5, 0, 5]
+        );
+        let counts = count_primes_by_last_digit(0, 1_000_000);
+        assert_eq!(counts.len(), 10);
+        assert_eq!(counts.values().sum::<usize>(), 78_498);
+        assert!(counts[&3] > counts[&1]);
+    }
+
+    #[test]
+    fn test_product_tree_matches_fold() {
+        assert_eq!(product_tree(&[]), BigUint::one());
+        assert_eq!(product_tree_u64(&[]), BigUint::one());
+        assert_eq!(product_tree_u64(&[u64::MAX]), BigUint::from(u64::MAX));
+        for len in [1, 2, 3, 7, 64, 1_000, 4_097] {
+            let factors: Vec<u64> = (0..len).map(|i| u64::MAX - i * 7_919).collect();
+            let folded = factors
+                .iter()
+                .fold(BigUint::one(), |product, &factor| product * factor);
+            let big_factors: Vec<BigUint> = factors.iter().map(|&f| BigUint::from(f)).collect();
+            assert_eq!(product_tree_u64(&factors), folded, "{len} factors");
+            assert_eq!(product_tree(&big_factors), folded, "{len} factors");
+        }
+    }
+
+    #[test]
+    fn test_primorial_iterative_matches_fold() {
+        assert_eq!(primorial_iterative(0, 10), BigUint::from(210_u32));
+        assert_eq!(primorial_iterative(4, 10), BigUint::from(35_u32));
+        assert_eq!(primorial_iterative(24, 28), BigUint::one());
+        for (lo, hi) in [(0, 1), (0, 2), (0, 1_000), (500, 20_000), (10_000, 100_000)] {
+            let folded = primes_up_to(hi)
+                .into_iter()
+                .filter(|&p| p >= lo)
+                .fold(BigUint::one(), |product, p| product * p);
+            assert_eq!(
+                primorial_iterative(lo, hi),
+                folded,
+                "primorial over [{lo}, {hi}]"
+            );
+        }
+    }
+
+    #[test]
+    fn test_prime_factorization() {
+        assert_eq!(prime_factorization(0), vec![]);
+        assert_eq!(prime_factorization(1), vec![]);
+        assert_eq!(prime_factorization(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(
+            prime_factorization(10_007 * 10_009),
+            vec![(10_007, 1), (10_009, 1)]
+        );
+        assert_eq!(prime_factorization(1_000_000_007), vec![(1_000_000_007, 1)]);
+        for n in 1..5_000_u64 {
+            let product: u64 = prime_factorization(n)
+                .into_iter()
+                .map(|(p, e)| p.pow(e))
+                .product();
+            assert_eq!(product, n);
+        }
+    }
+
+    #[test]
+    fn test_is_carmichael() {
+        for n in [561, 1105, 1729, 2465, 2821, 6601, 8911] {
+            assert!(is_carmichael(n), "{n} is a Carmichael number");
+        }
+        for n in [0, 1, 2, 3, 7, 97, 341, 560, 1_000_000_007] {
+            assert!(!is_carmichael(n), "{n} is not a Carmichael number");
+        }
+        let primes = primes_up_to(3_000);
+        for n in 2..3_000 {
+            let fermat_liar_to_all = primes.binary_search(&n).is_err()
+                && (1..n)
+                    .filter(|&a| a.gcd(&n) == 1)
+                    .all(|a| pow_mod(a, n - 1, n) == 1);
+            assert_eq!(is_carmichael(n), fermat_liar_to_all, "{n}");
+        }
+        assert_eq!((0..100_000).filter(|&n| is_carmichael(n)).count(), 16);
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_sum_reciprocal_primes_exact() {
+        assert_eq!(
+            sum_reciprocal_primes_exact(10),
+            (BigUint::from(247_u32), BigUint::from(210_u32))
+        );
+        assert_eq!(
+            sum_reciprocal_primes_exact(1),
+            (BigUint::ZERO, BigUint::one())
+        );
+        let (numerator, denominator) = sum_reciprocal_primes_exact(100);
+        let expected: f64 = primes_up_to(100).into_iter().map(|p| 1.0 / p as f64).sum();
+        let exact = numerator.to_f64().unwrap() / denominator.to_f64().unwrap();
+        assert!((exact - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_zeta_euler_product_approaches_basel() {
+        let basel = std::f64::consts::PI.powi(2) / 6.0;
+        let errors: Vec<f64> = [10, 100, 1_000, 100_000]
+            .into_iter()
+            .map(|limit| (basel - zeta_euler_product(2.0, limit).unwrap()).abs())
+            .collect();
+        assert!(errors.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(errors[3] < 1e-5);
+        assert!(zeta_euler_product(1.0, 100).is_err());
+    }
+
+    #[test]
+    fn test_liouville() {
+        assert_eq!(liouville(12).unwrap(), -1);
+        assert_eq!(liouville(1).unwrap(), 1);
+        assert_eq!(liouville(4).unwrap(), 1);
+        assert_eq!(liouville(97).unwrap(), -1);
+        assert!(liouville(0).is_err());
+        assert_eq!(liouville_sum(0), 0);
+        assert_eq!(liouville_sum(10), 0);
+        assert_eq!(liouville_sum(100), -2);
+        let mut running = 0;
+        for n in 1..=2_000 {
+            running += i64::from(liouville(n).unwrap());
+            assert_eq!(liouville_sum(n), running, "liouville_sum({n})");
+        }
+    }
+
+    #[test]
+    fn test_lru_cache_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        assert_eq!(cache.get(&1), Some("one"));
+        cache.insert(3, "three");
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some("one"));
+        assert_eq!(cache.get(&3), Some("three"));
+        cache.clear();
+        assert_eq!(cache.get(&1), None);
+    }
+
+    #[test]
+    fn test_cached_hits_and_skips_oversized_results() {
+        let cache: MathCache<u64> = LazyLock::new(|| Mutex::new(LruCache::new(4)));
+        let mut computed = 0;
+        for _ in 0..3 {
+            let value = cached(&cache, 4_321, || {
+                computed += 1;
+                Ok(factorial_biguint(4_321))
+            });
+            assert_eq!(value.unwrap(), factorial_biguint(4_321));
+        }
+        assert_eq!(computed, 1);
+
+        let huge = BigUint::one() << MATH_CACHE_MAX_BITS;
+        assert_eq!(cached(&cache, 1, || Ok(huge.clone())).unwrap(), huge);
+        assert_eq!(lock_cache(&cache).get(&1), None);
+        assert!(cached(&cache, 2, || Err(MathError::Value("failed"))).is_err());
+        assert_eq!(lock_cache(&cache).get(&2), None);
+    }
+
+    #[test]
+    fn test_math_cache_clear() {
+        let key = (9_001, 4_321);
+        let first = comb(key.0, key.1).unwrap();
+        assert_eq!(first, comb_uncached(key.0, key.1).unwrap());
+        assert_eq!(factorial(4_321).unwrap(), factorial_biguint(4_321));
+        clear_math_cache();
+        assert_eq!(comb(key.0, key.1).unwrap(), first);
+    }
+
+    #[test]
+    fn test_factorial_float() {
+        assert_eq!(factorial_float(5).unwrap(), 120.0);
+        assert_eq!(factorial_float(0).unwrap(), 1.0);
+        assert_eq!(factorial_float(200).unwrap(), f64::INFINITY);
+        assert_eq!(factorial_float(171).unwrap(), f64::INFINITY);
+        assert!(factorial_float(170).unwrap().is_finite());
+        for n in 21..=170 {
+            let exact = factorial_biguint(n as u64).to_f64().unwrap();
+            let relative_error = ((factorial_float(n).unwrap() - exact) / exact).abs();
+            assert!(relative_error < 1e-12, "factorial_float({n})");
+        }
+        assert!(factorial_float(-1).is_err());
+    }
+
+    #[test]
+    fn test_gcd_all() {
+        let values = |values: &[i64]| values.iter().map(|&v| BigInt::from(v)).collect::<Vec<_>>();
+        assert_eq!(gcd_all(values(&[12, 18, -24])), BigInt::from(6));
+        assert_eq!(gcd_all(values(&[-7])), BigInt::from(7));
+        assert_eq!(gcd_all(values(&[0, 0])), BigInt::ZERO);
+        assert_eq!(gcd_all(values(&[])), BigInt::ZERO);
+    }
+
+    #[test]
+    fn test_next_gap_at_least() {
+        assert_eq!(next_gap_at_least(2, 8, u64::MAX), Some(89));
+        assert_eq!(next_gap_at_least(0, 1, u64::MAX), Some(2));
+        assert_eq!(next_gap_at_least(2, 2, u64::MAX), Some(3));
+        assert_eq!(next_gap_at_least(89, 8, u64::MAX), Some(113));
+        assert_eq!(next_gap_at_least(0, 72, u64::MAX), Some(31_397));
+        assert_eq!(next_gap_at_least(0, 100, u64::MAX), Some(370_261));
+        assert_eq!(next_gap_at_least(0, 100, 370_261 + 111), None);
+        assert_eq!(next_gap_at_least(0, 100, 370_261 + 112), Some(370_261));
+        assert_eq!(next_gap_at_least(0, 1_000, 1 << 20), None);
+        assert_eq!(next_gap_at_least(u64::MAX, 2, u64::MAX), None);
+    }
+
+    #[test]
+    fn test_inverse_factorial() {
+        assert_eq!(inverse_factorial(BigUint::from(120_u32)).unwrap(), 5);
+        assert_eq!(inverse_factorial(BigUint::from(121_u32)).unwrap(), 5);
+        assert_eq!(inverse_factorial(BigUint::from(119_u32)).unwrap(), 4);
+        assert_eq!(inverse_factorial(BigUint::one()).unwrap(), 1);
+        assert!(inverse_factorial(BigUint::ZERO).is_err());
+        for n in 2..300 {
+            let factorial = factorial_biguint(n);
+            assert_eq!(inverse_factorial(factorial.clone()).unwrap(), n);
+            assert_eq!(inverse_factorial(factorial - 1_u32).unwrap(), n - 1);
+        }
+    }
+
+    #[test]
+    fn test_write_primes_to() {
+        let mut out = Vec::new();
+        write_primes_to(10, 50, &mut out, ", ").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47"
+        );
+
+        let mut out = Vec::new();
+        write_primes_to(0, 3 * SEGMENT_WIDTH, &mut out, "\n").unwrap();
+        let parsed: Vec<u64> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect();
+        assert_eq!(parsed, primes_up_to(3 * SEGMENT_WIDTH));
+
+        let mut out = Vec::new();
+        write_primes_to(24, 28, &mut out, ",").unwrap();
+        assert_eq!(out, b"");
+    }
+
+    #[test]
+    fn test_von_mangoldt() {
+        assert_eq!(von_mangoldt(8).unwrap(), 2.0_f64.ln());
+        assert_eq!(von_mangoldt(6).unwrap(), 0.0);
+        assert_eq!(von_mangoldt(1).unwrap(), 0.0);
+        assert_eq!(von_mangoldt(97).unwrap(), 97.0_f64.ln());
+        assert_eq!(von_mangoldt(3_u64.pow(40)).unwrap(), 3.0_f64.ln());
+        assert!(von_mangoldt(0).is_err());
+        assert_eq!(prime_power_base(1), None);
+        assert_eq!(prime_power_base(1 << 63), Some(2));
+        assert_eq!(prime_power_base(12), None);
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn test_chebyshev_functions_approach_x() {
+        let naive_psi: f64 = (1..=1_000).map(|n| von_mangoldt(n).unwrap()).sum();
+        assert!((chebyshev_psi(1_000) - naive_psi).abs() < 1e-9);
+        assert!(chebyshev_theta(1_000) < chebyshev_psi(1_000));
+        assert_eq!(chebyshev_psi(1), 0.0);
+
+        let distance_from_one = |f: fn(u64) -> f64, x: u64| (f(x) / x as f64 - 1.0).abs();
+        for f in [chebyshev_psi as fn(u64) -> f64, chebyshev_theta] {
+            let distances: Vec<f64> = [100, 10_000, 1_000_000]
+                .into_iter()
+                .map(|x| distance_from_one(f, x))
+                .collect();
+            assert!(distances.windows(2).all(|pair| pair[1] < pair[0]));
+            assert!(distances[2] < 0.002);
+        }
+    }
+
+    #[test]
+    fn test_comb_p_adic_valuation() {
+        assert_eq!(comb_p_adic_valuation(10, 3, 2).unwrap(), 3);
+        assert_eq!(comb_p_adic_valuation(10, 3, 3).unwrap(), 1);
+        assert_eq!(comb_p_adic_valuation(10, 3, 5).unwrap(), 1);
+        assert_eq!(comb_p_adic_valuation(10, 3, 7).unwrap(), 0);
+        for n in 0..=60_i64 {
+            for k in 0..=n {
+                let binomial = comb(n, k).unwrap();
+                for p in [2_u64, 3, 5, 7, 11, 13] {
+                    let mut valuation = 0;
+                    let mut remaining = binomial.clone();
+                    while (&remaining % p).is_zero() {
+                        remaining /= p;
+                        valuation += 1;
+                    }
+                    assert_eq!(
+                        comb_p_adic_valuation(n as u64, k as u64, p).unwrap(),
+                        valuation,
+                        "power of {p} in comb({n}, {k})"
+                    );
+                }
+            }
+        }
+        assert!(comb_p_adic_valuation(10, 3, 4).is_err());
+        assert!(comb_p_adic_valuation(3, 10, 2).is_err());
+    }
+
+    #[test]
+    fn test_divisor_summatory() {
+        assert_eq!(divisor_summatory(10).unwrap(), 27);
+        assert_eq!(divisor_summatory(0).unwrap(), 0);
+        assert_eq!(divisor_summatory(1).unwrap(), 1);
+        assert_eq!(divisor_summatory(10_000).unwrap(), 93_668);
+        for n in 1..=10_000 {
+            let naive = (1..=n).map(|k| n / k).sum::<u64>();
+            assert_eq!(divisor_summatory(n).unwrap(), naive, "D({n})");
+        }
+    }
+
+    #[test]
+    fn test_farey_sequence() {
+        assert_eq!(
+            farey_sequence(3).unwrap(),
+            vec![(0, 1), (1, 3), (1, 2), (2, 3), (1, 1)]
+        );
+        assert_eq!(farey_sequence(1).unwrap(), vec![(0, 1), (1, 1)]);
+        assert!(farey_sequence(0).is_err());
+        for n in 1..=60_u64 {
+            let sequence = farey_sequence(n).unwrap();
+            let mut expected: Vec<(u64, u64)> = (1..=n)
+                .flat_map(|den| (0..=den).map(move |num| (num, den)))
+                .filter(|&(num, den)| num.gcd(&den) == 1)
+                .collect();
+            expected.sort_by(|a, b| (a.0 * b.1).cmp(&(b.0 * a.1)));
+            assert_eq!(sequence, expected, "Farey sequence of order {n}");
+        }
+    }
+
+    #[test]
+    fn test_rational_approximation() {
+        let pi = std::f64::consts::PI;
+        assert_eq!(rational_approximation(pi, 10).unwrap(), (22, 7));
+        assert_eq!(rational_approximation(pi, 1_000).unwrap(), (355, 113));
+        assert_eq!(rational_approximation(-pi, 1_000).unwrap(), (-355, 113));
+        assert_eq!(rational_approximation(0.5, 1).unwrap(), (0, 1));
+        assert_eq!(rational_approximation(0.75, 100).unwrap(), (3, 4));
+        assert_eq!(rational_approximation(3.0, 5).unwrap(), (3, 1));
+        assert_eq!(rational_approximation(0.1, 1_000_000).unwrap(), (1, 10));
+        assert!(rational_approximation(f64::NAN, 10).is_err());
+        assert!(rational_approximation(1.5, 0).is_err());
+        assert!(rational_approximation(1e300, 10).is_err());
+    }
+
+    #[test]
+    fn test_pascal_row_mod() {
+        for (n, p) in [
+            (20_i64, 13_u64),
+            (0, 2),
+            (1, 2),
+            (12, 13),
+            (13, 13),
+            (100, 2),
+            (100, 7),
+            (60, 1_000_000_007),
+        ] {
+            let expected: Vec<u64> = (0..=n)
+                .map(|k| (comb(n, k).unwrap() % p).to_u64().unwrap())
+                .collect();
+            assert_eq!(
+                pascal_row_mod(n as u64, p).unwrap(),
+                expected,
+                "row {n} mod {p}"
+            );
+        }
+        assert!(pascal_row_mod(20, 12).is_err());
+    }
+
+    #[test]
+    fn test_primes_congruent() {
+        let mut primes = Vec::new();
+        iterate_primes_congruent(0, 30, 1, 4, |p| primes.push(p));
+        assert_eq!(primes, vec![5, 13, 17, 29]);
+
+        let mut primes = Vec::new();
+        iterate_primes_congruent(10, 30, 7, 4, |p| primes.push(p));
+        assert_eq!(primes, vec![11, 19, 23]);
+
+        assert_eq!(count_primes_congruent(0, 30, 1, 4).unwrap(), 4);
+        assert_eq!(count_primes_congruent(0, 100, 0, 1).unwrap(), 25);
+        assert_eq!(count_primes_congruent(0, 100, 0, 2).unwrap(), 1);
+        assert!(count_primes_congruent(0, 100, 1, 0).is_err());
+    }
+
+    #[test]
+    fn test_factorial_digit_chunks() {
+        for n in (0..=2000).step_by(7).chain([2000]) {
+            let expected = factorial(n).unwrap().to_string();
+            for chunk_digits in [1, 4, 9, 19] {
+                let chunks: Vec<String> =
+                    factorial_digit_chunks(n, chunk_digits).unwrap().collect();
+                assert_eq!(
+                    chunks.concat(),
+                    expected,
+                    "{n}! in {chunk_digits}-digit chunks"
+                );
+                assert!(
+                    chunks[1..]
+                        .iter()
+                        .all(|chunk| chunk.len() == chunk_digits as usize)
+                );
+            }
+        }
+        let expected = factorial(20_000).unwrap().to_string();
+        let mut chunks = factorial_digit_chunks(20_000, 9).unwrap();
+        let first = chunks.next().unwrap();
+        assert!(expected.starts_with(&first) && !first.starts_with('0'));
+        assert_eq!(first.len() + 9 * chunks.count(), expected.len());
+        assert!(factorial_digit_chunks(5, 0).is_err());
+        assert!(factorial_digit_chunks(5, 20).is_err());
+        assert!(factorial_digit_chunks(-1, 3).is_err());
+    }
+
+    #[test]
+    fn test_multiplicative_persistence() {
+        assert_eq!(multiplicative_persistence(39), 3);
+        assert_eq!(multiplicative_persistence(5), 0);
+        assert_eq!(multiplicative_persistence(10), 1);
+        assert_eq!(multiplicative_persistence(277_777_788_888_899), 11);
+        assert_eq!(multiplicative_persistence(u64::MAX), 1);
+    }
+
+    #[test]
+    fn test_prime_cache_extends_instead_of_rebuilding() {
+        let mut cache = PrimeCache::new();
+        assert_eq!(cache.primes_up_to(50_000), sieve_of_eratosthenes(50_000));
+        assert_eq!(cache.sieve_passes, 1);
+        assert_eq!(cache.primes_up_to(20_000), sieve_of_eratosthenes(20_000));
+        assert_eq!(cache.sieve_passes, 1);
+        assert_eq!(
+            cache.primes_up_to(1_000_000),
+            sieve_of_eratosthenes(1_000_000)
+        );
+        assert_eq!(cache.sieve_passes, 2);
+        assert_eq!(cache.primes_up_to(1), Vec::<u64>::new());
+
+        let mut small_steps = PrimeCache::new();
+        for limit in [0, 1, 2, 3, 10, 11, 120, 121, 20_000] {
+            assert_eq!(
+                small_steps.primes_up_to(limit),
+                sieve_of_eratosthenes(limit)
+            );
+        }
+
+        reset_prime_cache();
+        assert_eq!(primes_up_to(20_000), sieve_of_eratosthenes(20_000));
+    }
+
+    #[test]
+    fn test_primes_in_range_sieves_only_the_window() {
+        let low = 10_000_000_000;
+        let window = primes_in_range(low, low + 1_000);
+        let expected: Vec<u64> = (low..=low + 1_000).filter(|&n| is_prime_u64(n)).collect();
+        assert_eq!(window, expected);
+        assert_eq!(window.first(), Some(&10_000_000_019));
+        assert_eq!(primes_in_range(0, 30), primes_up_to(30));
+        assert_eq!(primes_in_range(30, 20), Vec::<u64>::new());
+        assert_eq!(
+            primes_in_range(u64::MAX - 200, u64::MAX),
+            [188, 178, 94, 82, 58].map(|offset| u64::MAX - offset)
+        );
+        let mut top = Vec::new();
+        iterate_primes_rev(u64::MAX - 100, u64::MAX, |p| top.push(p));
+        assert_eq!(top, [58, 82, 94].map(|offset| u64::MAX - offset));
+        let crossing = primes_in_range(SEGMENT_WIDTH - 100, SEGMENT_WIDTH + 100);
+        assert_eq!(
+            crossing,
+            primes_up_to(SEGMENT_WIDTH + 100)
+                .into_iter()
+                .filter(|&p| p >= SEGMENT_WIDTH - 100)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_primes_up_to_past_the_cache_limit() {
+        let n = PRIME_CACHE_LIMIT + 1_000;
+        let primes = primes_up_to(n);
+        assert_eq!(primes.len() as u64, count_primes_up_to(n));
+        assert_eq!(primes.last(), Some(&16_778_173));
+        assert!(PRIME_CACHE.lock().unwrap().limit <= PRIME_CACHE_LIMIT);
+    }
+
+    #[test]
+    fn test_smarandache() {
+        assert_eq!(smarandache(8).unwrap(), 4);
+        assert_eq!(smarandache(6).unwrap(), 3);
+        assert_eq!(smarandache(1).unwrap(), 1);
+        assert_eq!(smarandache(16).unwrap(), 6);
+        for p in first_n_primes(200) {
+            assert_eq!(smarandache(p).unwrap(), p);
+        }
+        for n in 1..300_u64 {
+            let expected = (1..=300)
+                .find(|&m| (factorial(m).unwrap() % n).is_zero())
+                .unwrap();
+            assert_eq!(smarandache(n).unwrap(), expected as u64, "S({n})");
+        }
+        assert!(smarandache(0).is_err());
+    }
+
+    #[test]
+    fn test_abundancy_index() {
+        let two = (BigUint::from(2_u8), BigUint::one());
+        assert_eq!(abundancy_index(6).unwrap(), two);
+        assert_eq!(abundancy_index(28).unwrap(), two);
+        assert_eq!(abundancy_index(8_128).unwrap(), two);
+        assert_eq!(
+            abundancy_index(1).unwrap(),
+            (BigUint::one(), BigUint::one())
+        );
+        assert_eq!(
+            abundancy_index(12).unwrap(),
+            (BigUint::from(7_u8), BigUint::from(3_u8))
+        );
+        assert_eq!(
+            abundancy_index(30).unwrap(),
+            (BigUint::from(12_u8), BigUint::from(5_u8))
+        );
+        assert!(abundancy_index(0).is_err());
+    }
+
+    #[test]
+    fn test_amicable_pair() {
+        assert_eq!(amicable_pair(220), Some(284));
+        assert_eq!(amicable_pair(284), Some(220));
+        assert_eq!(amicable_pair(221), None);
+        assert_eq!(amicable_pair(1_184), Some(1_210));
+        assert_eq!(amicable_pair(6), None);
+        assert_eq!(amicable_pair(1), None);
+        assert_eq!(amicable_pair(0), None);
+    }
+
+    #[test]
+    fn test_is_prime_beyond_u64() {
+        assert!(!is_prime(BigUint::one() << 100));
+        assert!(is_prime((BigUint::one() << 127) - 1_u8));
+        assert!(!is_prime((BigUint::one() << 64) + 1_u8));
+        assert!(is_prime(BigUint::from(u64::MAX - 58)));
+        assert!(!is_prime(BigUint::from(u64::MAX)));
+        assert!(!is_prime(BigUint::ZERO));
+    }
+
+    #[test]
+    fn test_count_primes_in_bertrand_interval() {
+        for n in 1..=1000 {
+            let count = count_primes_in_bertrand_interval(n).unwrap();
+            assert!(count > 0, "no prime in ({n}, {}]", 2 * n);
+            let expected = (n + 1..=2 * n).filter(|&m| is_prime_u64(m)).count();
+            assert_eq!(count, expected, "primes in ({n}, {}]", 2 * n);
+        }
+        assert_eq!(count_primes_in_bertrand_interval(0).unwrap(), 0);
+        assert!(count_primes_in_bertrand_interval(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_prime_signature() {
+        assert_eq!(prime_signature(12), vec![2, 1]);
+        assert_eq!(prime_signature(360), vec![3, 2, 1]);
+        assert_eq!(prime_signature(1), Vec::<u32>::new());
+        assert_eq!(prime_signature(1_024), vec![10]);
+        assert_eq!(prime_signature(2 * 3 * 3 * 5 * 5 * 5 * 7), vec![3, 2, 1, 1]);
+    }
+
+    #[test]
+    fn test_jordan_totient() {
+        for n in 1..500_u64 {
+            let totient = (1..=n).filter(|&m| m.gcd(&n) == 1).count();
+            assert_eq!(
+                jordan_totient(n, 1).unwrap(),
+                BigUint::from(totient),
+                "phi({n})"
+            );
+        }
+        assert_eq!(jordan_totient(6, 2).unwrap(), BigUint::from(24_u8));
+        assert_eq!(jordan_totient(12, 3).unwrap(), BigUint::from(1_456_u16));
+        assert_eq!(jordan_totient(1, 5).unwrap(), BigUint::one());
+        assert_eq!(jordan_totient(10, 0).unwrap(), BigUint::ZERO);
+        assert!(jordan_totient(0, 2).is_err());
+    }
+
+    #[test]
+    fn test_iterate_primes_rev() {
+        let mut descending = Vec::new();
+        iterate_primes_rev(1, 30, |p| descending.push(p));
+        let mut ascending = primes_up_to(30);
+        ascending.reverse();
+        assert_eq!(descending, ascending);
+
+        let mut window = Vec::new();
+        iterate_primes_rev(20_000, 20_100, |p| window.push(p));
+        assert_eq!(
+            window,
+            [
+                20_089, 20_071, 20_063, 20_051, 20_047, 20_029, 20_023, 20_021, 20_011
+            ]
+        );
+
+        let mut empty = Vec::new();
+        iterate_primes_rev(24, 28, |p| empty.push(p));
+        assert_eq!(empty, Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_comb_batch() {
+        let pairs = [(0_i64, 0_i64), (5, 2), (10, 10), (52, 5), (100, 50), (7, 9)];
+        let expected: Vec<BigUint> = pairs.iter().map(|&(n, k)| comb(n, k).unwrap()).collect();
+        let ns = pairs.iter().map(|&(n, _)| n as u64).collect();
+        let ks = pairs.iter().map(|&(_, k)| k as u64).collect();
+        assert_eq!(comb_batch(ns, ks).unwrap(), expected);
+        assert_eq!(comb_batch(vec![], vec![]).unwrap(), Vec::<BigUint>::new());
+        assert!(comb_batch(vec![1, 2], vec![1]).is_err());
+        assert!(comb_batch(vec![u64::MAX], vec![1]).is_err());
+    }
+
+    #[test]
+    fn test_totient_summatory() {
+        assert_eq!(totient_summatory(0), BigUint::ZERO);
+        assert_eq!(totient_summatory(1), BigUint::one());
+        assert_eq!(totient_summatory(5), BigUint::from(10_u8));
+        let mut naive = BigUint::ZERO;
+        for n in 1..=10_000_u64 {
+            naive += jordan_totient(n, 1).unwrap();
+            if n % 97 == 0 || n == 10_000 {
+                assert_eq!(totient_summatory(n), naive, "Phi({n})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_mertens() {
+        assert_eq!(mertens(0), 0);
+        assert_eq!(mertens(1), 1);
+        assert_eq!(mertens(10), -1);
+        assert_eq!(mobius(30).unwrap(), -1);
+        assert_eq!(mobius(12).unwrap(), 0);
+        assert!(mobius(0).is_err());
+        let mut naive = 0;
+        for n in 1..=5_000 {
+            naive += i64::from(mobius(n).unwrap());
+            if n % 61 == 0 || n == 5_000 {
+                assert_eq!(mertens(n), naive, "M({n})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_totient_image_count() {
+        assert_eq!(totient_image_count(0), 0);
+        assert_eq!(totient_image_count(1), 1);
+        assert_eq!(totient_image_count(10), 4);
+        for n in [2, 7, 30, 100, 1_000] {
+            let values: std::collections::HashSet<BigUint> =
+                (1..=n).map(|k| jordan_totient(k, 1).unwrap()).collect();
+            assert_eq!(totient_image_count(n), values.len(), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn test_repunit_period() {
+        assert_eq!(repunit_period(7), Some(6));
+        assert_eq!(repunit_period(3), Some(1));
+        assert_eq!(repunit_period(11), Some(2));
+        assert_eq!(repunit_period(12), Some(1));
+        assert_eq!(repunit_period(17), Some(16));
+        assert_eq!(repunit_period(37), Some(3));
+        assert_eq!(repunit_period(1), None);
+        assert_eq!(repunit_period(40), None);
+        assert_eq!(repunit_period(0), None);
+        for n in 1..500_u64 {
+            let coprime_part = (1..=n)
+                .rev()
+                .find(|&d| n.is_multiple_of(d) && d.gcd(&10) == 1)
+                .unwrap();
+            let expected = (coprime_part > 1).then(|| {
+                let mut remainder = 10 % coprime_part;
+                let mut period = 1;
+                while remainder != 1 {
+                    remainder = remainder * 10 % coprime_part;
+                    period += 1;
+                }
+                period
+            });
+            assert_eq!(repunit_period(n), expected, "1/{n}");
+        }
+    }
+
+    #[test]
+    fn test_convergents_of_e() {
+        let fractions = |pairs: &[(u32, u32)]| -> Vec<(BigUint, BigUint)> {
+            pairs
+                .iter()
+                .map(|&(h, k)| (BigUint::from(h), BigUint::from(k)))
+                .collect()
+        };
+        assert_eq!(
+            convergents_of_e(9),
+            fractions(&[
+                (2, 1),
+                (3, 1),
+                (8, 3),
+                (11, 4),
+                (19, 7),
+                (87, 32),
+                (106, 39),
+                (193, 71),
+                (1_264, 465)
+            ])
+        );
+        assert_eq!(convergents_of_e(0), Vec::new());
+        assert_eq!(
+            convergents(&[1, 2, 2, 2]),
+            fractions(&[(1, 1), (3, 2), (7, 5), (17, 12)])
+        );
+        let (h, k) = convergents_of_e(30).pop().unwrap();
+        let approximation = h.to_f64().unwrap() / k.to_f64().unwrap();
+        assert!((approximation - std::f64::consts::E).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_is_primorial_prime() {
+        assert_eq!(primorial_iterative(2, 11) + 1_u8, BigUint::from(2_311_u16));
+        assert_eq!(is_primorial_prime(2_311), Some(11));
+        assert_eq!(is_primorial_prime(2_309), Some(11));
+        assert_eq!(is_primorial_prime(3), Some(2));
+        assert_eq!(is_primorial_prime(5), Some(3));
+        assert_eq!(is_primorial_prime(7), Some(3));
+        assert_eq!(is_primorial_prime(211), Some(7));
+        assert_eq!(is_primorial_prime(30_029), Some(13));
+        assert_eq!(is_primorial_prime(30_031), None);
+        assert_eq!(is_primorial_prime(209), None);
+        assert_eq!(is_primorial_prime(13), None);
+        assert_eq!(is_primorial_prime(1), None);
+        assert_eq!(is_primorial_prime(u64::MAX - 58), None);
+    }
+
+    #[test]
+    fn test_is_factorial_prime() {
+        assert_eq!(is_factorial_prime(5), Some(3));
+        assert_eq!(is_factorial_prime(7), Some(3));
+        assert_eq!(is_factorial_prime(2), Some(1));
+        assert_eq!(is_factorial_prime(3), Some(2));
+        assert_eq!(is_factorial_prime(23), Some(4));
+        assert_eq!(is_factorial_prime(719), Some(6));
+        assert_eq!(is_factorial_prime(5_039), Some(7));
+        assert_eq!(is_factorial_prime(121), None);
+        assert_eq!(is_factorial_prime(11), None);
+        assert_eq!(is_factorial_prime(1), None);
+        for k in 1..=20 {
+            let n = factorial(k).unwrap().to_u64().unwrap();
+            for candidate in [n - 1, n + 1] {
+                if is_prime_u64(candidate) {
+                    assert!(is_factorial_prime(candidate).is_some(), "{candidate}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rational_pow() {
+        let fraction = |h: i64, k: u64| (BigInt::from(h), BigUint::from(k));
+        assert_eq!(rational_pow(2, 3, 3).unwrap(), fraction(8, 27));
+        assert_eq!(rational_pow(2, 3, -2).unwrap(), fraction(9, 4));
+        assert_eq!(rational_pow(-2, 3, -3).unwrap(), fraction(-27, 8));
+        assert_eq!(rational_pow(4, 6, 2).unwrap(), fraction(4, 9));
+        assert_eq!(rational_pow(5, 7, 0).unwrap(), fraction(1, 1));
+        assert_eq!(rational_pow(0, 7, 3).unwrap(), fraction(0, 1));
+        assert_eq!(rational_pow(i64::MIN, 1, 1).unwrap(), fraction(i64::MIN, 1));
+        assert_eq!(rational_pow(i64::MIN, 1 << 63, 5).unwrap(), fraction(-1, 1));
+        assert!(rational_pow(1, 0, 1).is_err());
+        assert!(rational_pow(0, 3, -1).is_err());
+        assert!(rational_pow(2, 3, i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_ackermann() {
+        assert_eq!(ackermann(0, 0).unwrap(), BigUint::one());
+        assert_eq!(ackermann(2, 3).unwrap(), BigUint::from(9_u8));
+        assert_eq!(ackermann(3, 3).unwrap(), BigUint::from(61_u8));
+        assert_eq!(ackermann(4, 0).unwrap(), BigUint::from(13_u8));
+        assert_eq!(ackermann(4, 1).unwrap(), BigUint::from(65_533_u32));
+        assert_eq!(ackermann(5, 0).unwrap(), BigUint::from(65_533_u32));
+        assert_eq!(ackermann(4, 2).unwrap(), (BigUint::one() << 65_536) - 3_u8);
+        for m in 1..=3 {
+            assert_eq!(ackermann(m, 0).unwrap(), ackermann(m - 1, 1).unwrap());
+            for n in 1..20 {
+                let inner = ackermann(m, n - 1).unwrap().to_u64().unwrap();
+                assert_eq!(ackermann(m, n).unwrap(), ackermann(m - 1, inner).unwrap());
+            }
+        }
+        assert!(ackermann(4, 3).is_err());
+        assert!(ackermann(5, 1).is_err());
+        assert!(ackermann(6, 0).is_err());
+        assert!(ackermann(3, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_palindromes_and_reversal() {
+        assert!(is_palindrome(factorial(0).unwrap(), 10).unwrap());
+        assert!(is_palindrome(BigUint::ZERO, 10).unwrap());
+        assert!(is_palindrome(BigUint::from(12_321_u32), 10).unwrap());
+        assert!(!is_palindrome(BigUint::from(1_232_u32), 10).unwrap());
+        assert!(is_palindrome(BigUint::from(0b1001_u8), 2).unwrap());
+        assert!(is_palindrome(BigUint::from(10_u8).pow(40) + 1_u8, 10).unwrap());
+        assert_eq!(
+            reverse_digits(BigUint::from(1_234_u32), 10).unwrap(),
+            BigUint::from(4_321_u32)
+        );
+        assert_eq!(
+            reverse_digits(BigUint::from(1_200_u32), 10).unwrap(),
+            BigUint::from(21_u8)
+        );
+        assert_eq!(
+            reverse_digits(BigUint::from(0b1101_u8), 2).unwrap(),
+            BigUint::from(0b1011_u8)
+        );
+        let big = factorial(50).unwrap();
+        let expected: String = big.to_string().chars().rev().collect();
+        assert_eq!(
+            reverse_digits(big, 10).unwrap(),
+            expected.parse::<BigUint>().unwrap()
+        );
+        assert!(is_palindrome(BigUint::one(), 1).is_err());
+        assert!(reverse_digits(BigUint::one(), 0).is_err());
+    }
+
+    #[test]
+    fn test_collatz_length() {
+        assert_eq!(collatz_length(1).unwrap(), 0);
+        assert_eq!(collatz_length(6).unwrap(), 8);
+        assert_eq!(collatz_length(27).unwrap(), 111);
+        assert_eq!(collatz_length(837_799).unwrap(), 524);
+        assert_eq!(collatz_length(1 << 63).unwrap(), 63);
+        assert!(collatz_length(u64::MAX).unwrap() > 0);
+        assert!(collatz_length(0).is_err());
+    }
+
+    #[test]
+    fn test_goldbach_count() {
+        assert_eq!(goldbach_count(10).unwrap(), 2);
+        assert_eq!(goldbach_count(4).unwrap(), 1);
+        assert_eq!(goldbach_count(2).unwrap(), 0);
+        assert_eq!(goldbach_count(0).unwrap(), 0);
+        assert_eq!(goldbach_count(100).unwrap(), 6);
+        for n in (4..2_000).step_by(2) {
+            assert!(goldbach_count(n).unwrap() > 0, "{n}");
+        }
+        assert!(goldbach_count(9).is_err());
+    }
+
+    #[test]
+    fn test_count_prime_triplets() {
+        assert_eq!(count_prime_triplets(0, 100, [0, 2, 6]).unwrap(), 4);
+        assert_eq!(count_prime_triplets(0, 100, [0, 4, 6]).unwrap(), 4);
+        assert_eq!(count_prime_triplets(0, 46, [0, 2, 6]).unwrap(), 3);
+        assert_eq!(count_prime_triplets(12, 100, [0, 2, 6]).unwrap(), 2);
+        assert_eq!(count_prime_triplets(0, 1_000, [0, 2, 6]).unwrap(), 15);
+        assert!(count_prime_triplets(0, 100, [0, 2, 4]).is_err());
+    }
+
+    #[test]
+    fn test_is_factorion() {
+        assert!(is_factorion(145));
+        assert!(is_factorion(40_585));
+        assert!(!is_factorion(146));
+        assert!(is_factorion(1));
+        assert!(is_factorion(2));
+        assert!(!is_factorion(0));
+        assert!(!is_factorion(u64::MAX));
+        assert_eq!(
+            (0..100_000)
+                .filter(|&n| is_factorion(n))
+                .collect::<Vec<_>>(),
+            [1, 2, 145, 40_585]
+        );
+    }
+
+    #[test]
+    fn test_random_smooth_number() {
+        for seed in 0..20 {
+            let smooth = random_smooth_number(256, 1_000, Some(seed)).unwrap();
+            assert!((256..256 + 10).contains(&smooth.bits()));
+            let mut remaining = smooth.clone();
+            for p in primes_up_to(1_000) {
+                while (&remaining % p).is_zero() {
+                    remaining /= p;
+                }
+            }
+            assert_eq!(remaining, BigUint::one(), "{smooth} is not 1000-smooth");
+        }
+        assert_eq!(
+            random_smooth_number(64, 50, Some(7)).unwrap(),
+            random_smooth_number(64, 50, Some(7)).unwrap()
+        );
+        assert_eq!(random_smooth_number(0, 2, None).unwrap(), BigUint::one());
+        assert!(random_smooth_number(64, 1, None).is_err());
+    }
+
+    #[test]
+    fn test_largest_prime_factor_big() {
+        assert_eq!(
+            largest_prime_factor_big(1_000_000_007 * 1_000_000_009).unwrap(),
+            1_000_000_009
+        );
+        assert_eq!(
+            largest_prime_factor_big(4_294_967_291 * 4_294_967_279).unwrap(),
+            4_294_967_291
+        );
+        assert_eq!(largest_prime_factor_big(u64::MAX).unwrap(), 6_700_417);
+        assert_eq!(
+            largest_prime_factor_big(u64::MAX - 58).unwrap(),
+            u64::MAX - 58
+        );
+        assert_eq!(largest_prime_factor_big(1 << 63).unwrap(), 2);
+        assert_eq!(largest_prime_factor_big(3_u64.pow(40)).unwrap(), 3);
+        assert_eq!(
+            largest_prime_factor_big(1_000_003 * 1_000_003).unwrap(),
+            1_000_003
+        );
+        for n in 2..2_000 {
+            let expected = prime_factorization(n).last().unwrap().0;
+            assert_eq!(largest_prime_factor_big(n).unwrap(), expected, "{n}");
+        }
+        assert!(largest_prime_factor_big(1).is_err());
+    }
+
+    #[test]
+    fn test_factorize_u64() {
+        assert_eq!(
+            factorize_u64(1_000_000_007 * 1_000_000_009),
+            [(1_000_000_007, 1), (1_000_000_009, 1)]
+        );
+        assert_eq!(
+            factorize_u64(u64::MAX),
+            [
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65_537, 1),
+                (6_700_417, 1)
+            ]
+        );
+        assert_eq!(factorize_u64(u64::MAX - 58), [(u64::MAX - 58, 1)]);
+        assert_eq!(
+            factorize_u64(2 * 2 * 10_007 * 10_007 * 10_009),
+            [(2, 2), (10_007, 2), (10_009, 1)]
+        );
+        assert_eq!(
+            factorize_u64(4_294_967_291 * 4_294_967_291),
+            [(4_294_967_291, 2)]
+        );
+        assert_eq!(factorize_u64(1), []);
+        assert_eq!(factorize_u64(0), []);
+        for n in 2..5_000_u64 {
+            let product: u64 = factorize_u64(n)
+                .into_iter()
+                .map(|(p, exponent)| {
+                    assert!(is_prime_u64(p));
+                    p.pow(exponent)
+                })
+                .product();
+            assert_eq!(product, n);
+        }
+    }
+
+    #[test]
+    fn test_aliquot() {
+        assert_eq!(aliquot_sum(12).unwrap(), BigUint::from(16_u8));
+        assert_eq!(aliquot_sum(6).unwrap(), BigUint::from(6_u8));
+        assert_eq!(aliquot_sum(1).unwrap(), BigUint::ZERO);
+        assert_eq!(aliquot_sum(7).unwrap(), BigUint::one());
+        assert!(aliquot_sum(0).is_err());
+
+        let sequence: Vec<u64> = aliquot_sequence(12, 100).collect();
+        assert_eq!(sequence, [12, 16, 15, 9, 4, 3, 1, 0]);
+        assert_eq!(aliquot_sequence(12, 2).collect::<Vec<_>>(), [12, 16, 15]);
+        assert_eq!(
+            aliquot_sequence(220, 4).collect::<Vec<_>>(),
+            [220, 284, 220, 284, 220]
+        );
+        assert_eq!(
+            aliquot_sequence(28, 3).collect::<Vec<_>>(),
+            [28, 28, 28, 28]
+        );
+        assert_eq!(aliquot_sequence(0, 5).collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    fn test_is_practical() {
+        for n in [
+            1, 2, 4, 6, 8, 12, 16, 18, 20, 24, 28, 30, 32, 36, 40, 42, 48,
+        ] {
+            assert!(is_practical(n), "{n}");
+        }
+        for n in [0, 3, 5, 7, 9, 10, 14, 22, 26, 34] {
+            assert!(!is_practical(n), "{n}");
+        }
+        for n in 1..300_u64 {
+            let divisors: Vec<u64> = (1..=n).filter(|&d| n.is_multiple_of(d)).collect();
+            let mut reachable = vec![false; usize::try_from(n).unwrap()];
+            reachable[0] = true;
+            for &d in &divisors {
+                for total in (usize::try_from(d).unwrap()..reachable.len()).rev() {
+                    reachable[total] |= reachable[total - usize::try_from(d).unwrap()];
+                }
+            }
+            assert_eq!(is_practical(n), reachable.iter().all(|&r| r), "{n}");
+        }
+    }
+
+    #[test]
+    fn test_factorial_as() {
+        for n in [0, 1, 5, 20, 21, 100, 500] {
+            let expected = factorial(n).unwrap();
+            assert_eq!(
+                factorial_as(n, "int").unwrap(),
+                FactorialRepr::Int(expected.clone())
+            );
+            let FactorialRepr::Hex(hex) = factorial_as(n, "hex").unwrap() else {
+                panic!("hex format did not produce a string");
+            };
+            assert_eq!(BigUint::parse_bytes(hex.as_bytes(), 16).unwrap(), expected);
+            let FactorialRepr::Bytes(bytes) = factorial_as(n, "bytes").unwrap() else {
+                panic!("bytes format did not produce bytes");
+            };
+            assert_eq!(BigUint::from_bytes_be(&bytes), expected);
+        }
+        assert_eq!(
+            factorial_as(5, "hex").unwrap(),
+            FactorialRepr::Hex("78".to_string())
+        );
+        assert!(factorial_as(5, "octal").is_err());
+        assert!(factorial_as(-1, "int").is_err());
+    }
+
+    #[test]
+    fn test_incremental_isqrt() {
+        let mut incremental = IncrementalIsqrt::new();
+        for n in 0..1_000_000_u64 {
+            let root = incremental.isqrt(n);
+            assert_eq!(root, n.isqrt(), "{n}");
+            if n.is_multiple_of(997) {
+                assert_eq!(root, isqrt(n.cast_signed()).unwrap() as u64, "{n}");
+            }
+        }
+        let start = (1_u64 << 62) - 5_000;
+        let mut incremental = IncrementalIsqrt::new();
+        for n in (start..start + 10_000).chain((0..200).map(|k| start + k * k * 1_000)) {
+            assert_eq!(
+                incremental.isqrt(n),
+                isqrt(n.cast_signed()).unwrap() as u64,
+                "{n}"
+            );
+        }
+        assert_eq!(incremental.isqrt(99), 9);
+        assert_eq!(incremental.isqrt(10_000), 100);
+        assert_eq!(incremental.isqrt(u64::MAX), u64::from(u32::MAX));
+    }
+
+    #[test]
+    fn test_count_primes_with_binary_weight() {
+        // 3 = 0b11, 5 = 0b101, 17 = 0b10001; 257 and 65537 are the only other weight-2 primes below 2^17
+        assert_eq!(count_primes_with_binary_weight(1, 100, 2), 3);
+        assert_eq!(count_primes_with_binary_weight(1, 100_000, 2), 5);
+        // 7, 11, 13, 19 are 0b111, 0b1011, 0b1101, 0b10011
+        assert_eq!(count_primes_with_binary_weight(1, 20, 3), 4);
+        assert_eq!(count_primes_with_binary_weight(1, 100, 1), 1);
+        assert_eq!(count_primes_with_binary_weight(10, 20, 4), 0);
+        let total: usize = (0..=17)
+            .map(|weight| count_primes_with_binary_weight(1_000, 100_000, weight))
+            .sum();
+        assert_eq!(total, primes_up_to(100_000).len() - primes_up_to(999).len());
+    }
+
+    #[test]
+    fn test_nth_prime_between_widens_a_short_window() {
+        assert_eq!(nth_prime_between(1_000, 7_000, 7_100).unwrap(), 7_919);
+        assert_eq!(nth_prime_between(1_000, 7_950, 8_000).unwrap(), 7_919);
+        assert_eq!(nth_prime_between(1_000, 7_918, 7_919).unwrap(), 7_919);
+        assert_eq!(nth_prime_between(1_000, 7_919, 7_920).unwrap(), 7_919);
+        assert_eq!(nth_prime_between(1_000, 7_918, 7_918).unwrap(), 7_919);
+    }
+
+    #[test]
+    fn test_nth_prime_fast() {
+        let primes = first_n_primes(100_000);
+        for k in (1..=100_000)
+            .step_by(997)
+            .chain(1..=40)
+            .chain(1_225..=1_235)
+            .chain([100_000])
+        {
+            assert_eq!(
+                nth_prime_fast(k).unwrap(),
+                primes[usize::try_from(k - 1).unwrap()],
+                "p_{k}"
+            );
+        }
+        assert_eq!(nth_prime_fast(1_000_000).unwrap(), 15_485_863);
+        assert!(nth_prime_fast(0).is_err());
+        for n in [0, 1, 2, 3, 4, 100, 10_000, 1_000_001] {
+            assert_eq!(
+                count_primes_up_to(n),
+                primes_up_to(n).len() as u64,
+                "pi({n})"
+            );
+        }
+        assert_eq!(sieve_segment(20, 10, &[2, 3]), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_cumulative_prime_sums() {
+        let sums: Vec<(u64, BigUint)> = cumulative_prime_sums(1, 20).collect();
+        let expected = [
+            (2, 2_u32),
+            (3, 5),
+            (5, 10),
+            (7, 17),
+            (11, 28),
+            (13, 41),
+            (17, 58),
+            (19, 77),
+        ];
+        assert_eq!(sums, expected.map(|(p, sum)| (p, BigUint::from(sum))));
+        let (last, total) = cumulative_prime_sums(1_000, 200_000).last().unwrap();
+        assert_eq!(last, 199_999);
+        let direct: u64 = primes_up_to(200_000)
+            .into_iter()
+            .filter(|&p| p >= 1_000)
+            .sum();
+        assert_eq!(total, BigUint::from(direct));
+        assert_eq!(cumulative_prime_sums(24, 28).count(), 0);
+        let crossing =
+            cumulative_prime_sums(0, 1_000).find(|(_, sum)| *sum > BigUint::from(1_000_u32));
+        assert_eq!(crossing.map(|(p, _)| p), Some(97));
+    }
+
+    #[test]
+    fn test_polynomial_prime_values() {
+        let euler = polynomial_prime_values(vec![41, 1, 1], 0, 41);
+        for (n, value) in euler[..40].iter().enumerate() {
+            assert_eq!(*value, Some(n as u64 * n as u64 + n as u64 + 41), "n = {n}");
+        }
+        assert_eq!(euler[40], None);
+        assert_eq!(
+            polynomial_prime_values(vec![-1, 0, 1], 2, 3),
+            [Some(3), None, None]
+        );
+        assert_eq!(polynomial_prime_values(vec![-5], 0, 2), [None, None]);
+        assert_eq!(polynomial_prime_values(vec![], 0, 1), [None]);
+        assert_eq!(
+            polynomial_prime_values(vec![0, 0, 0, 1], u64::MAX - 1, 1),
+            [None]
+        );
+    }
+
+    #[test]
+    fn test_radical_range() {
+        assert_eq!(radical(1).unwrap(), 1);
+        assert_eq!(radical(72).unwrap(), 6);
+        assert_eq!(radical(u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(
+            radical_range(1, 12).unwrap(),
+            [1, 2, 3, 2, 5, 6, 7, 2, 3, 10, 11, 6]
+        );
+        let range = radical_range(9_000, 10_000).unwrap();
+        for (n, &value) in (9_000..=10_000).zip(&range) {
+            assert_eq!(value, radical(n).unwrap(), "rad({n})");
+        }
+        assert_eq!(radical_range(10, 9).unwrap(), Vec::<u64>::new());
+        assert!(radical(0).is_err());
+        assert!(radical_range(0, 5).is_err());
+    }
+
+    #[test]
+    fn test_prime_compositions() {
+        assert_eq!(prime_compositions(0), BigUint::one());
+        assert_eq!(prime_compositions(1), BigUint::ZERO);
+        assert_eq!(prime_compositions(2), BigUint::one());
+        assert_eq!(prime_compositions(4), BigUint::one());
+        // 5 = 5, 2+3, 3+2
+        assert_eq!(prime_compositions(5), BigUint::from(3_u8));
+        // 7 = 7, 2+5, 5+2, 2+2+3, 2+3+2, 3+2+2
+        assert_eq!(prime_compositions(7), BigUint::from(6_u8));
+        assert_eq!(prime_compositions(10), BigUint::from(16_u8));
+    }
+
+    #[test]
+    fn test_prime_partitions() {
+        assert_eq!(prime_partitions(0), BigUint::one());
+        assert_eq!(prime_partitions(1), BigUint::ZERO);
+        assert_eq!(prime_partitions(5), BigUint::from(2_u8));
+        assert_eq!(prime_partitions(10), BigUint::from(5_u8));
+        assert_eq!(prime_partitions(100), BigUint::from(40_899_u32));
+    }
+
+    #[test]
+    fn test_sophie_germain_primes() {
+        assert_eq!(sophie_germain_primes(0, 30), [2, 3, 5, 11, 23, 29]);
+        assert_eq!(sophie_germain_primes(0, 29), [2, 3, 5, 11, 23, 29]);
+        assert_eq!(sophie_germain_primes(0, 28), [2, 3, 5, 11, 23]);
+        assert_eq!(sophie_germain_primes(24, 28), Vec::<u64>::new());
+        let mut count = 0;
+        iterate_sophie_germain(1, 1_000, |p| {
+            assert!(is_prime_u64(p) && is_prime_u64(2 * p + 1));
+            count += 1;
+        });
+        assert_eq!(count, 37);
+        assert_eq!(
+            sophie_germain_primes(1 << 63, (1 << 63) + 200),
+            Vec::<u64>::new()
+        );
+        let top = (u64::MAX - 1) / 2;
+        let expected: Vec<u64> = (top - 5_000..=top + 200)
+            .filter(|&p| is_prime_u64(p) && p <= top && is_prime_u64(2 * p + 1))
+            .collect();
+        assert_ne!(expected, Vec::<u64>::new());
+        assert_eq!(sophie_germain_primes(top - 5_000, top + 200), expected);
+    }
+
+    #[test]
+    fn test_is_safe_prime() {
+        assert!(is_safe_prime(23));
+        assert!(is_safe_prime(47));
+        assert!(is_safe_prime(5));
+        assert!(!is_safe_prime(13));
+        assert!(!is_safe_prime(3));
+        assert!(!is_safe_prime(2));
+        assert!(!is_safe_prime(0));
+        for p in sophie_germain_primes(0, 1_000) {
+            assert!(is_safe_prime(2 * p + 1), "{}", 2 * p + 1);
+        }
+        assert_eq!((0..1_000).filter(|&p| is_safe_prime(p)).count(), 25);
+    }
+
+    #[test]
+    fn test_prime_quadruplets() {
+        assert_eq!(prime_quadruplets(0, 20), [5, 11]);
+        assert_eq!(prime_quadruplets(0, 12), Vec::<u64>::new());
+        assert_eq!(prime_quadruplets(6, 18), Vec::<u64>::new());
+        assert_eq!(prime_quadruplets(6, 19), [11]);
+        assert_eq!(
+            prime_quadruplets(0, 2_000),
+            [5, 11, 101, 191, 821, 1_481, 1_871]
+        );
+        let mut count = 0;
+        iterate_prime_quadruplets(100, 200, |p| {
+            assert!([p, p + 2, p + 6, p + 8].into_iter().all(is_prime_u64));
+            count += 1;
+        });
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_gcd_sum() {
+        assert_eq!(gcd_sum(6), BigUint::from(15_u8));
+        assert_eq!(gcd_sum(1), BigUint::one());
+        assert_eq!(gcd_sum(0), BigUint::ZERO);
+        for n in 1..=1_000_u64 {
+            let naive: u64 = (1..=n).map(|k| k.gcd(&n)).sum();
+            assert_eq!(gcd_sum(n), BigUint::from(naive), "g({n})");
+        }
+    }
+
+    #[test]
+    fn test_sum_of_two_squares_count() {
+        let direct = |n: u64| {
+            (1..=n.isqrt())
+                .filter(|&a| {
+                    let rest = n - a * a;
+                    rest.isqrt() * rest.isqrt() == rest
+                })
+                .count() as u64
+        };
+        assert_eq!(sum_of_two_squares_count(25), direct(25));
+        assert_eq!(sum_of_two_squares_count(25), 3);
+        for n in 1..5_000 {
+            assert_eq!(sum_of_two_squares_count(n), direct(n), "{n}");
+        }
+        assert_eq!(sum_of_two_squares_count(3), 0);
+        assert_eq!(sum_of_two_squares_count(7 * 7 * 7 * 2), 0);
+        assert_eq!(sum_of_two_squares_count(9 * 5), 2);
+        assert_eq!(sum_of_two_squares_count(0), 0);
+    }
+
+    #[test]
+    fn test_totient_chain_length() {
+        assert_eq!(totient_chain_length(1).unwrap(), 0);
+        assert_eq!(totient_chain_length(2).unwrap(), 1);
+        assert_eq!(totient_chain_length(5).unwrap(), 3);
+        assert_eq!(totient_chain_length(1 << 40).unwrap(), 40);
+        assert_eq!(totient_chain_length(3_u64.pow(20)).unwrap(), 21);
+        assert!(totient_chain_length(0).is_err());
+        let sieve = LinearSieve::new(1_000);
+        for n in 1..=1_000_u64 {
+            assert_eq!(
+                totient(n).unwrap(),
+                sieve.totients()[usize::try_from(n).unwrap()],
+                "phi({n})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_comb_factorization() {
+        assert_eq!(comb_factorization(10, 3).unwrap(), [(2, 3), (3, 1), (5, 1)]);
+        assert_eq!(comb_factorization(7, 0).unwrap(), []);
+        assert_eq!(comb_factorization(0, 0).unwrap(), []);
+        for (n, k) in [
+            (10_i64, 5_i64),
+            (52, 5),
+            (100, 50),
+            (1_000, 333),
+            (97, 1),
+            (64, 32),
+        ] {
+            let product: BigUint = comb_factorization(n as u64, k as u64)
+                .unwrap()
+                .into_iter()
+                .map(|(p, exponent)| BigUint::from(p).pow(u32::try_from(exponent).unwrap()))
+                .product();
+            assert_eq!(product, comb(n, k).unwrap(), "C({n}, {k})");
+        }
+        assert!(comb_factorization(3, 4).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_factorial_log10() {
+        for n in 0..=1_000_i64 {
+            let digits = factorial(n).unwrap().to_string().len();
+            assert_eq!(
+                factorial_log10(n as u64).floor() as usize + 1,
+                digits,
+                "{n}!"
+            );
+        }
+        assert!((factorial_log10(10) - 3_628_800_f64.log10()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_primes_matching() {
+        assert_eq!(
+            primes_matching(0, 100, "_3").unwrap(),
+            [13, 23, 43, 53, 73, 83]
+        );
+        assert_eq!(
+            primes_matching(0, 1_000, "1_3").unwrap(),
+            [103, 113, 163, 173, 193]
+        );
+        assert_eq!(primes_matching(0, 100, "7").unwrap(), [7]);
+        assert_eq!(primes_matching(0, 100, "").unwrap(), Vec::<u64>::new());
+        assert!(primes_matching(0, 100, "1?3").is_err());
+
+        let mut palindromes = Vec::new();
+        iterate_primes_matching(
+            100,
+            200,
+            |digits| digits.iter().eq(digits.iter().rev()),
+            |p| palindromes.push(p),
+        );
+        assert_eq!(palindromes, [101, 131, 151, 181, 191]);
+    }
+
+    #[test]
+    fn test_product_range_mod() {
+        assert_eq!(product_range_mod(1, 5, 1_000).unwrap(), 120);
+        for (start, end, m) in [
+            (1_u64, 3_000_u64, 1_000_000_007_u64),
+            (500, 2_500, 998_244_353),
+            (u64::MAX - 100, u64::MAX, u64::MAX - 58),
+            (7, 12, 720),
+            (10, 20, 1),
+        ] {
+            let expected = (product_range(end, start) % m).to_u64().unwrap();
+            assert_eq!(
+                product_range_mod(start, end, m).unwrap(),
+                expected,
+                "[{start}, {end}] mod {m}"
+            );
+        }
+        assert_eq!(product_range_mod(5, 4, 7).unwrap(), 1);
+        assert_eq!(product_range_mod(3, 9, 7).unwrap(), 0);
+        assert!(product_range_mod(1, 5, 0).is_err());
+    }
+
+    #[test]
+    fn test_comb_trailing_zeros() {
+        let trailing_zeros = |value: BigUint, base: u32| {
+            value
+                .to_str_radix(base)
+                .bytes()
+                .rev()
+                .take_while(|&digit| digit == b'0')
+                .count() as u64
+        };
+        for (n, k) in [
+            (10_i64, 5_i64),
+            (100, 50),
+            (1_000, 500),
+            (200, 3),
+            (125, 25),
+            (64, 32),
+            (7, 0),
+        ] {
+            for base in [2, 10, 12, 16, 36] {
+                assert_eq!(
+                    comb_trailing_zeros(n as u64, k as u64, u64::from(base)).unwrap(),
+                    trailing_zeros(comb(n, k).unwrap(), base),
+                    "C({n}, {k}) in base {base}"
+                );
+            }
+        }
+        assert!(comb_trailing_zeros(5, 6, 10).is_err());
+        assert!(comb_trailing_zeros(5, 2, 1).is_err());
+    }
+
+    #[test]
+    fn test_figurate() {
+        assert_eq!(figurate(3, 4).unwrap(), BigUint::from(10_u8));
+        assert_eq!(figurate(4, 7).unwrap(), BigUint::from(49_u8));
+        assert_eq!(figurate(5, 5).unwrap(), BigUint::from(35_u8));
+        assert_eq!(figurate(6, 3).unwrap(), BigUint::from(15_u8));
+        assert_eq!(figurate(3, 0).unwrap(), BigUint::ZERO);
+        assert!(is_figurate(3, 10).unwrap());
+        assert!(!is_figurate(3, 11).unwrap());
+        assert!(is_figurate(4, 0).unwrap());
+        for sides in 3..=12 {
+            let values: Vec<u64> = (0..200)
+                .map(|n| figurate(sides, n).unwrap().to_u64().unwrap())
+                .collect();
+            for x in 0..=*values.last().unwrap() {
+                assert_eq!(
+                    is_figurate(sides, x).unwrap(),
+                    values.binary_search(&x).is_ok(),
+                    "{x} as a {sides}-gonal number"
+                );
+            }
+        }
+        assert!(is_figurate(u64::MAX, u64::MAX).unwrap());
+        assert!(figurate(2, 5).is_err());
+        assert!(is_figurate(2, 5).is_err());
+    }
+
+    #[test]
+    fn test_comb_is_odd() {
+        for n in [0_i64, 1, 7, 8, 10, 31, 64, 100, 255, 1_000] {
+            for k in 0..=n {
+                assert_eq!(
+                    comb_is_odd(n as u64, k as u64),
+                    comb(n, k).unwrap().bit(0),
+                    "C({n}, {k})"
+                );
+            }
+        }
+        assert!(!comb_is_odd(3, 5));
+        assert!(comb_is_odd(u64::MAX, 12_345));
+    }
+
+    #[test]
+    fn test_sum_prime_digit_sums() {
+        // 2 + 3 + 5 + 7 + (1+1) + (1+3) + (1+7) + (1+9) = 41
+        assert_eq!(sum_prime_digit_sums(1, 20), 41);
+        // 23, 29 -> 5 + 11
+        assert_eq!(sum_prime_digit_sums(21, 30), 16);
+        assert_eq!(sum_prime_digit_sums(24, 28), 0);
+        assert_eq!(sum_prime_digit_sums(97, 97), 16);
+    }
+
+    #[test]
+    fn test_fraction_period() {
+        assert_eq!(fraction_period(6, 10).unwrap(), (1, 1));
+        assert_eq!(fraction_period(7, 10).unwrap(), (0, 6));
+        assert_eq!(fraction_period(8, 10).unwrap(), (3, 0));
+        assert_eq!(fraction_period(1, 10).unwrap(), (0, 0));
+        assert_eq!(fraction_period(12, 10).unwrap(), (2, 1));
+        assert_eq!(fraction_period(3, 2).unwrap(), (0, 2));
+        assert_eq!(fraction_period(8, 4).unwrap(), (2, 0));
+        assert_eq!(fraction_period(10, 2).unwrap(), (1, 4));
+        for n in 1..300_u64 {
+            let (_, period) = fraction_period(n, 10).unwrap();
+            assert_eq!(repunit_period(n).unwrap_or(0), period, "1/{n}");
+        }
+        assert!(fraction_period(0, 10).is_err());
+        assert!(fraction_period(7, 1).is_err());
+    }
+
+    #[test]
+    fn test_dedekind_psi() {
+        assert_eq!(dedekind_psi(1).unwrap(), BigUint::from(1_u8));
+        assert_eq!(dedekind_psi(6).unwrap(), BigUint::from(12_u8));
+        assert_eq!(dedekind_psi(12).unwrap(), BigUint::from(24_u8));
+        assert_eq!(dedekind_psi(8).unwrap(), BigUint::from(12_u8));
+        for p in primes_up_to(1000) {
+            assert_eq!(dedekind_psi(p).unwrap(), BigUint::from(p + 1));
+        }
+        assert_eq!(
+            dedekind_psi(u64::MAX).unwrap(),
+            BigUint::from(4_u8) * 6_u8 * 18_u8 * 258_u16 * 642_u16 * 65538_u32 * 6_700_418_u32
+        );
+        assert!(dedekind_psi(0).is_err());
+    }
+
+    #[test]
+    fn test_is_harshad() {
+        assert!(is_harshad(18, 10).unwrap());
+        assert!(is_harshad(21, 10).unwrap());
+        assert!(!is_harshad(19, 10).unwrap());
+        assert!((1..=10).all(|n| is_harshad(n, 10).unwrap()));
+        assert!(is_harshad(1729, 10).unwrap());
+        // 6 = 0b110 has digit sum 2, 7 = 0b111 has digit sum 3
+        assert!(is_harshad(6, 2).unwrap());
+        assert!(!is_harshad(7, 2).unwrap());
+        assert!(is_harshad(0, 10).is_err());
+        assert!(is_harshad(18, 1).is_err());
+    }
+
+    #[test]
+    fn test_next_harshad() {
+        assert_eq!(next_harshad(0, 10).unwrap(), 1);
+        assert_eq!(next_harshad(10, 10).unwrap(), 12);
+        assert_eq!(next_harshad(18, 10).unwrap(), 20);
+        assert_eq!(next_harshad(20, 10).unwrap(), 21);
+        assert_eq!(next_harshad(6, 2).unwrap(), 8);
+        assert!(next_harshad(u64::MAX, 10).is_err());
+        assert!(next_harshad(18, 0).is_err());
+    }
+
+    #[test]
+    fn test_icbrt() {
+        for r in 0..2000_u64 {
+            assert_eq!(icbrt(r * r * r), r);
+            if r > 0 {
+                assert_eq!(icbrt(r * r * r - 1), r - 1);
+            }
+        }
+        assert_eq!(icbrt(u64::MAX), 2_642_245);
+    }
+
+    #[test]
+    fn test_ways_as_sum_of_two_cubes() {
+        assert_eq!(ways_as_sum_of_two_cubes(1729), vec![(1, 12), (9, 10)]);
+        assert_eq!(ways_as_sum_of_two_cubes(2), vec![(1, 1)]);
+        assert_eq!(ways_as_sum_of_two_cubes(16), vec![(2, 2)]);
+        assert_eq!(ways_as_sum_of_two_cubes(3), vec![]);
+        assert_eq!(ways_as_sum_of_two_cubes(0), vec![]);
+        assert_eq!(ways_as_sum_of_two_cubes(1), vec![]);
+        assert_eq!(
+            ways_as_sum_of_two_cubes(87_539_319),
+            vec![(167, 436), (228, 423), (255, 414)]
+        );
+    }
+
+    #[bench]
+    fn bench_product_tree_u64(b: &mut Bencher) {
+        let factors: Vec<u64> = (1..=20_000).collect();
+        b.iter(|| product_tree_u64(&factors));
+    }
+
+    #[bench]
+    fn bench_naive_product_u64(b: &mut Bencher) {
+        let factors: Vec<u64> = (1..=20_000).collect();
+        b.iter(|| {
+            factors
+                .iter()
+                .fold(BigUint::one(), |product, &factor| product * factor)
+        });
+    }
+
+    #[bench]
+    fn bench_incremental_isqrt(b: &mut Bencher) {
+        b.iter(|| {
+            let mut incremental = IncrementalIsqrt::new();
+            (1_000_000..1_100_000)
+                .map(|n| incremental.isqrt(n))
+                .sum::<u64>()
+        });
+    }
+
+    #[bench]
+    fn bench_isqrt_each(b: &mut Bencher) {
+        b.iter(|| {
+            (1_000_000..1_100_000)
+                .map(|n| isqrt(n).unwrap())
+                .sum::<i64>()
+        });
     }
 
     #[bench]
@@ -0,0 +1,91 @@
+use num_bigint::{BigInt, BigUint};
+use rand::{SeedableRng, rngs::StdRng};
+use rstd::math::{self, MathError};
+
+#[test]
+fn factorial_without_python() {
+    assert_eq!(math::factorial(10).unwrap(), BigUint::from(3_628_800_u32));
+    assert!(matches!(math::factorial(-1), Err(MathError::Value(_))));
+}
+
+#[test]
+fn comb_without_python() {
+    assert_eq!(math::comb(10, 3).unwrap(), BigUint::from(120_u32));
+}
+
+#[test]
+fn primes_without_python() {
+    assert_eq!(math::primes_up_to(20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+
+    let mut out = Vec::new();
+    math::write_primes_to(10, 20, &mut out, ",").unwrap();
+    assert_eq!(out, b"11,13,17,19");
+}
+
+#[test]
+fn gcd_all_without_python() {
+    let values = [12, 18, -30].map(BigInt::from);
+    assert_eq!(math::gcd_all(values), BigInt::from(6));
+}
+
+#[test]
+fn product_tree_without_python() {
+    let factors: Vec<BigUint> = (1..=20_u32).map(BigUint::from).collect();
+    assert_eq!(math::product_tree(&factors), math::factorial(20).unwrap());
+    assert_eq!(math::product_tree(&[]), BigUint::from(1_u8));
+    assert_eq!(
+        math::product_tree_u64(&[u64::MAX, u64::MAX, 3]),
+        BigUint::from(u64::MAX).pow(2) * 3_u8
+    );
+    assert_eq!(math::product_tree_u64(&[]), BigUint::from(1_u8));
+}
+
+#[test]
+fn random_prime_with_caller_rng() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..100 {
+        let p = math::random_prime_with_rng(1_000, 2_000, &mut rng).unwrap();
+        assert!((1_000..=2_000).contains(&p) && math::is_prime(BigUint::from(p)));
+    }
+    assert_eq!(math::random_prime_with_rng(24, 28, &mut rng), None);
+    let mut first = StdRng::seed_from_u64(42);
+    let mut second = StdRng::seed_from_u64(42);
+    assert_eq!(
+        math::random_prime_with_rng(0, 1 << 20, &mut first),
+        math::random_prime_with_rng(0, 1 << 20, &mut second)
+    );
+}
+
+#[test]
+fn prime_with_bit_length_with_caller_rng() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for bits in [2, 3, 17, 64, 65, 128] {
+        let p = math::prime_with_bit_length_with_rng(bits, &mut rng).unwrap();
+        assert_eq!(p.bits(), u64::from(bits));
+        assert!(math::is_prime(p));
+    }
+    assert!(matches!(
+        math::prime_with_bit_length_with_rng(1, &mut rng),
+        Err(MathError::Value(_))
+    ));
+}
+
+#[test]
+fn random_smooth_number_with_caller_rng() {
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..20 {
+        let n = math::random_smooth_number_with_rng(64, 30, &mut rng).unwrap();
+        assert!(n.bits() >= 64);
+        let mut rest = n;
+        for p in math::primes_up_to(30) {
+            while (&rest % p) == BigUint::ZERO {
+                rest /= p;
+            }
+        }
+        assert_eq!(rest, BigUint::from(1_u8));
+    }
+    assert!(matches!(
+        math::random_smooth_number_with_rng(64, 1, &mut rng),
+        Err(MathError::Value(_))
+    ));
+}